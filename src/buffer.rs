@@ -0,0 +1,413 @@
+//! Buffers: the area within a distance of a geometry.
+//!
+//! A buffer is built as the union of simple pieces: a rectangle along each
+//! segment, a wedge at each vertex where the segments turn, and caps at the
+//! ends of lines. Polygons grow by their own area joined with the buffers of
+//! their rings, and shrink by having the buffers of their rings cut away.
+//! Curves are approximated by `quadrant_segments` segments per quarter
+//! circle.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::{overlay::union_all, Geom, GeomType, Line, Point, Poly, Ring};
+
+/// How the ends of lines are finished.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CapStyle {
+    /// A half circle around the end.
+    #[default]
+    Round,
+    /// Cut off square at the end.
+    Flat,
+    /// A half square extending past the end.
+    Square,
+}
+
+/// How the outside of corners is filled in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum JoinStyle {
+    /// An arc around the corner.
+    #[default]
+    Round,
+    /// The offset edges extended until they meet, unless that is further
+    /// than the mitre limit times the distance, in which case the corner is
+    /// bevelled.
+    Mitre,
+    /// A straight line across the corner.
+    Bevel,
+}
+
+/// Options for [`Geom::buffer`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BufferOptions {
+    /// The number of segments used to approximate a quarter circle.
+    pub quadrant_segments: u32,
+    pub cap_style: CapStyle,
+    pub join_style: JoinStyle,
+    /// The furthest a mitred corner may reach, as a multiple of the
+    /// distance.
+    pub mitre_limit: f64,
+}
+
+impl Default for BufferOptions {
+    fn default() -> BufferOptions {
+        BufferOptions {
+            quadrant_segments: 8,
+            cap_style: CapStyle::Round,
+            join_style: JoinStyle::Round,
+            mitre_limit: 5.,
+        }
+    }
+}
+
+impl BufferOptions {
+    pub fn with_quadrant_segments(mut self, quadrant_segments: u32) -> BufferOptions {
+        self.quadrant_segments = quadrant_segments;
+        self
+    }
+
+    pub fn with_cap_style(mut self, cap_style: CapStyle) -> BufferOptions {
+        self.cap_style = cap_style;
+        self
+    }
+
+    pub fn with_join_style(mut self, join_style: JoinStyle) -> BufferOptions {
+        self.join_style = join_style;
+        self
+    }
+
+    pub fn with_mitre_limit(mut self, mitre_limit: f64) -> BufferOptions {
+        self.mitre_limit = mitre_limit;
+        self
+    }
+}
+
+/// Buffers
+impl Geom {
+    /// The area within `distance` of the geometry, as a polygon, or as a
+    /// multipolygon if it falls into several pieces or is empty.
+    ///
+    /// A negative distance shrinks polygons, and leaves nothing of points and
+    /// lines. Distances are in the units of the coordinates, so lon/lat
+    /// geometries should be projected first.
+    pub fn buffer(&self, distance: f64, options: BufferOptions) -> Geom {
+        let mut polys = Vec::new();
+        let mut pieces = Vec::new();
+        let builder = Builder {
+            distance: distance.abs(),
+            options,
+        };
+        collect(self, &builder, &mut polys, &mut pieces);
+
+        let result = if distance > 0. {
+            for poly in &polys {
+                builder.ring_pieces(poly, &mut pieces);
+            }
+            polys.append(&mut pieces);
+            union_all(polys)
+        } else if distance < 0. {
+            let mut result = Vec::new();
+            for poly in polys {
+                let mut cutters = Vec::new();
+                builder.ring_pieces(&poly, &mut cutters);
+                let mut remaining = vec![poly];
+                for cutter in union_all(cutters) {
+                    remaining = remaining
+                        .iter()
+                        .flat_map(|poly| {
+                            let cut = poly.difference(&cutter);
+                            (0..cut.num_polys()).filter_map(move |i| cut.poly_at(i))
+                        })
+                        .collect();
+                }
+                result.append(&mut remaining);
+            }
+            result
+        } else {
+            union_all(polys)
+        };
+        match <[Poly; 1]>::try_from(result) {
+            Ok([poly]) => poly.geom(),
+            Err(polys) => Geom::new_multipolygon(&polys),
+        }
+    }
+}
+
+/// Gather the polygons of `geom`, and the pieces of the buffers of its points
+/// and lines if the distance is positive.
+fn collect(geom: &Geom, builder: &Builder, polys: &mut Vec<Poly>, pieces: &mut Vec<Poly>) {
+    if geom.is_empty() {
+        return;
+    }
+    let positive = builder.distance > 0.;
+    match geom.geom_type() {
+        GeomType::Point if positive => builder.point_pieces(geom.point(), pieces),
+        GeomType::MultiPoint if positive => {
+            for point in (0..geom.num_points()).filter_map(|i| geom.point_at(i)) {
+                builder.point_pieces(point, pieces);
+            }
+        }
+        GeomType::LineString if positive => {
+            if let Some(line) = geom.line() {
+                builder.line_pieces(&line, pieces);
+            }
+        }
+        GeomType::MultiLineString if positive => {
+            for line in (0..geom.num_lines()).filter_map(|i| geom.line_at(i)) {
+                builder.line_pieces(&line, pieces);
+            }
+        }
+        GeomType::Polygon => polys.extend(geom.poly()),
+        GeomType::MultiPolygon => polys.extend((0..geom.num_polys()).filter_map(|i| geom.poly_at(i))),
+        GeomType::GeometryCollection => {
+            for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                collect(&child, builder, polys, pieces);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds the pieces of a buffer.
+struct Builder {
+    distance: f64,
+    options: BufferOptions,
+}
+
+impl Builder {
+    /// The point `distance` from `point` in the direction of the unit vector
+    /// `normal`.
+    fn offset(&self, point: Point, normal: (f64, f64)) -> Point {
+        Point::new(point.x + normal.0 * self.distance, point.y + normal.1 * self.distance)
+    }
+
+    /// The points of an arc around `center`, starting at `from` in the
+    /// direction of the unit vector `normal`, turning `sweep` radians
+    /// counter-clockwise (clockwise if negative) and ending at `to`.
+    fn arc(&self, center: Point, from: Point, normal: (f64, f64), sweep: f64, to: Point) -> Vec<Point> {
+        let step = FRAC_PI_2 / self.options.quadrant_segments.max(1) as f64;
+        let steps = (sweep.abs() / step).ceil().max(1.) as usize;
+        let start = normal.1.atan2(normal.0);
+        let mut points = vec![from];
+        points.extend((1..steps).map(|k| {
+            let angle = start + sweep * k as f64 / steps as f64;
+            self.offset(center, (angle.cos(), angle.sin()))
+        }));
+        points.push(to);
+        points
+    }
+
+    fn point_pieces(&self, point: Point, pieces: &mut Vec<Poly>) {
+        match self.options.cap_style {
+            CapStyle::Round => {
+                let from = self.offset(point, (1., 0.));
+                let mut points = self.arc(point, from, (1., 0.), TAU, from);
+                points.pop();
+                pieces.push(piece(points));
+            }
+            CapStyle::Square => {
+                let (a, b) = (self.offset(point, (-1., -1.)), self.offset(point, (1., 1.)));
+                pieces.push(piece(vec![a, Point::new(b.x, a.y), b, Point::new(a.x, b.y)]));
+            }
+            CapStyle::Flat => {}
+        }
+    }
+
+    fn line_pieces(&self, line: &Line, pieces: &mut Vec<Poly>) {
+        let mut points = line.points().to_vec();
+        points.dedup();
+        match points.len() {
+            0 => {}
+            1 => self.point_pieces(points[0], pieces),
+            _ => {
+                let closed = points.len() > 3 && points.first() == points.last();
+                self.path_pieces(&points, closed, pieces);
+            }
+        }
+    }
+
+    /// The pieces of the buffers of the rings of `poly`.
+    fn ring_pieces(&self, poly: &Poly, pieces: &mut Vec<Poly>) {
+        for ring in std::iter::once(poly.exterior()).chain(poly.holes()) {
+            let mut points = ring.points().to_vec();
+            points.dedup();
+            if points.len() > 3 {
+                self.path_pieces(&points, true, pieces);
+            }
+        }
+    }
+
+    /// The pieces along a path of distinct consecutive points, which ends
+    /// where it starts if `closed`.
+    fn path_pieces(&self, points: &[Point], closed: bool, pieces: &mut Vec<Poly>) {
+        let normals: Vec<(f64, f64)> = points.windows(2).map(|pair| left_normal(pair[0], pair[1])).collect();
+        for (pair, &normal) in points.windows(2).zip(&normals) {
+            let (a, b) = (pair[0], pair[1]);
+            let right = (-normal.0, -normal.1);
+            pieces.push(piece(vec![
+                self.offset(a, normal),
+                self.offset(a, right),
+                self.offset(b, right),
+                self.offset(b, normal),
+            ]));
+        }
+        for i in 1..normals.len() {
+            self.join(points[i], normals[i - 1], normals[i], pieces);
+        }
+        if closed {
+            self.join(points[0], normals[normals.len() - 1], normals[0], pieces);
+        } else {
+            let n = points.len();
+            self.cap(points[0], normals[0], pieces);
+            let last = normals[n - 2];
+            self.cap(points[n - 1], (-last.0, -last.1), pieces);
+        }
+    }
+
+    /// The wedge filling the outside of the corner at `vertex`, between
+    /// segments with left normals `into` and `out`.
+    fn join(&self, vertex: Point, into: (f64, f64), out: (f64, f64), pieces: &mut Vec<Poly>) {
+        let turn = into.0 * out.1 - into.1 * out.0;
+        let dot = into.0 * out.0 + into.1 * out.1;
+        if turn == 0. && dot > 0. {
+            return;
+        }
+        // The outside of a left turn is on the right.
+        let (from, to) = if turn > 0. {
+            ((-into.0, -into.1), (-out.0, -out.1))
+        } else {
+            (into, out)
+        };
+        // Turning all the way back goes around the far side of the vertex.
+        let sweep = if turn == 0. { -PI } else { turn.atan2(dot) };
+        let (start, end) = (self.offset(vertex, from), self.offset(vertex, to));
+        let mut points = vec![vertex];
+        match self.options.join_style {
+            JoinStyle::Round => points.extend(self.arc(vertex, start, from, sweep, end)),
+            // The offset edges meet 1 / cos(sweep / 2) from the vertex.
+            JoinStyle::Mitre if 2. / (1. + dot) <= self.options.mitre_limit.powi(2) => {
+                let scale = 1. / (1. + dot);
+                let tip = self.offset(vertex, ((from.0 + to.0) * scale, (from.1 + to.1) * scale));
+                points.extend([start, tip, end]);
+            }
+            JoinStyle::Mitre | JoinStyle::Bevel if turn != 0. => points.extend([start, end]),
+            JoinStyle::Mitre | JoinStyle::Bevel => return,
+        }
+        pieces.push(piece(points));
+    }
+
+    /// The cap past `end`, where `normal` is the unit vector a quarter turn
+    /// clockwise from the direction leading out past the end.
+    fn cap(&self, end: Point, normal: (f64, f64), pieces: &mut Vec<Poly>) {
+        let outward = (-normal.1, normal.0);
+        let (left, right) = (self.offset(end, normal), self.offset(end, (-normal.0, -normal.1)));
+        match self.options.cap_style {
+            CapStyle::Round => pieces.push(piece(self.arc(end, left, normal, PI, right))),
+            CapStyle::Square => pieces.push(piece(vec![
+                left,
+                self.offset(left, outward),
+                self.offset(right, outward),
+                right,
+            ])),
+            CapStyle::Flat => {}
+        }
+    }
+}
+
+/// The unit vector a quarter turn counter-clockwise from the direction of
+/// `a` to `b`.
+fn left_normal(a: Point, b: Point) -> (f64, f64) {
+    let length = a.distance(b);
+    (-(b.y - a.y) / length, (b.x - a.x) / length)
+}
+
+/// A polygon from the points of its exterior, in either winding order.
+fn piece(mut points: Vec<Point>) -> Poly {
+    points.push(points[0]);
+    Poly::from(Ring::new(&points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BufferOptions, CapStyle, JoinStyle};
+    use crate::test_util::square;
+    use crate::{Geom, GeomType, Line, Point, Poly, Ring};
+
+    fn area(geom: &Geom) -> f64 {
+        let polys: Vec<Poly> = match geom.geom_type() {
+            GeomType::Polygon => geom.poly().into_iter().collect(),
+            _ => (0..geom.num_polys()).filter_map(|i| geom.poly_at(i)).collect(),
+        };
+        polys
+            .iter()
+            .map(|poly| poly.exterior().area() - poly.holes().iter().map(Ring::area).sum::<f64>())
+            .sum()
+    }
+
+    #[test]
+    fn points() {
+        let options = BufferOptions::default().with_quadrant_segments(16);
+        let circle = Point::new(3., 4.).geom().buffer(2., options);
+        assert_eq!(circle.geom_type(), GeomType::Polygon);
+        assert_eq!(circle.poly().unwrap().exterior().num_points(), 65);
+        let exact = std::f64::consts::PI * 4.;
+        assert!(area(&circle) < exact && area(&circle) > exact * 0.99);
+
+        let square = Point::new(0., 0.).geom().buffer(1., options.with_cap_style(CapStyle::Square));
+        assert_eq!(area(&square), 4.);
+
+        let apart = Geom::new_multipoint(&[Point::new(0., 0.), Point::new(10., 0.)]);
+        assert_eq!(apart.buffer(1., options).num_polys(), 2);
+        let overlapping = Geom::new_multipoint(&[Point::new(0., 0.), Point::new(1., 0.)]);
+        assert_eq!(overlapping.buffer(1., options).geom_type(), GeomType::Polygon);
+
+        assert!(Point::new(0., 0.).geom().buffer(-1., options).is_empty());
+    }
+
+    #[test]
+    fn lines() {
+        let road = Line::new(&[Point::new(0., 0.), Point::new(10., 0.), Point::new(10., 10.)]).geom();
+        let flat = BufferOptions::default()
+            .with_cap_style(CapStyle::Flat)
+            .with_join_style(JoinStyle::Mitre);
+        let corridor = road.buffer(1., flat);
+        assert_eq!(corridor.geom_type(), GeomType::Polygon);
+        // two 10 × 2 strips, less the 1 × 1 square where they overlap
+        assert_eq!(area(&corridor), 40.);
+        assert!(corridor.covers(&Point::new(10.9, -0.9).geom()));
+        assert_eq!(corridor.poly().unwrap().exterior().num_points(), 7);
+
+        let bevel = road.buffer(1., flat.with_join_style(JoinStyle::Bevel));
+        assert_eq!(area(&bevel), 39.5);
+        let square = road.buffer(1., flat.with_cap_style(CapStyle::Square));
+        assert_eq!(area(&square), 44.);
+
+        let round = road.buffer(1., BufferOptions::default());
+        assert!(round.covers(&road));
+        assert!(round.covers(&Point::new(-0.99, 0.).geom()));
+        assert!(!round.intersects(&Point::new(5., 1.01).geom()));
+        assert!(!round.intersects(&Point::new(5., 5.).geom()));
+    }
+
+    #[test]
+    fn polygons() {
+        let options = BufferOptions::default().with_join_style(JoinStyle::Mitre);
+        let zone = Poly::from(square(0., 0., 10.)).geom();
+        let grown = zone.buffer(1., options);
+        assert!(grown.eq_topo(&Poly::from(square(-1., -1., 12.)).geom()));
+        let shrunk = zone.buffer(-1., options);
+        assert!(shrunk.eq_topo(&Poly::from(square(1., 1., 8.)).geom()));
+        assert!(zone.buffer(-6., options).is_empty());
+        assert!(zone.buffer(0., options).eq_topo(&zone));
+
+        // the hole shrinks as the polygon grows
+        let holed = Poly::new(&square(0., 0., 10.), &[square(3., 3., 4.)]).geom();
+        assert_eq!(area(&holed.buffer(1., options)), 144. - 4.);
+        assert_eq!(area(&holed.buffer(-1., options)), 64. - 36.);
+        // and fills in when it grows enough
+        assert_eq!(area(&holed.buffer(2.5, options)), 225.);
+
+        let round = zone.buffer(1., BufferOptions::default());
+        assert!(area(&round) > 100. + 40. + 3.1 && area(&round) < 100. + 40. + std::f64::consts::PI);
+    }
+}
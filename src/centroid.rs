@@ -0,0 +1,566 @@
+//! Centroids and label points.
+//!
+//! - `centroid` is the center of mass. It is weighted by area for polygons,
+//!   by length for lines, and is the mean for points. It can fall outside of
+//!   concave or holed shapes.
+//! - `interior_point` is cheap to compute and always lies on the geometry: in
+//!   the interior of a polygon, on a vertex of a line.
+//! - `pole_of_inaccessibility` is the interior point farthest from the
+//!   boundary of a polygon, found with the polylabel algorithm to within
+//!   `precision`. It is the best place for a label, but the most expensive.
+
+use std::{cmp::Ordering, collections::BinaryHeap, f64::consts::SQRT_2};
+
+use crate::{Geom, GeomType, Line, Point, Poly, Ring};
+
+/// Centroids and label points
+impl Line {
+    /// The length-weighted center of the line's segments.
+    ///
+    /// A line with zero length has the mean of its points as its centroid.
+    pub fn centroid(&self) -> Point {
+        let mut sum = CentroidSum::default();
+        sum.add_line(self.points());
+        sum.centroid().unwrap_or_else(|| self.rect().center())
+    }
+
+    /// The vertex closest to the centroid, preferring vertices other than the
+    /// endpoints.
+    pub fn interior_point(&self) -> Point {
+        let mut nearest = NearestVertex::new(self.centroid());
+        nearest.add_line(self.points());
+        nearest.point.unwrap_or_else(|| self.rect().center())
+    }
+}
+
+/// Centroids and label points
+impl Ring {
+    /// The area-weighted center of the ring.
+    ///
+    /// A ring with zero area has the centroid of its boundary.
+    pub fn centroid(&self) -> Point {
+        let mut sum = CentroidSum::default();
+        sum.add_ring(self.vertices(), 1.);
+        sum.centroid().unwrap_or_else(|| self.rect().center())
+    }
+
+    /// A point guaranteed to be inside the ring.
+    ///
+    /// See [`Poly::interior_point`].
+    pub fn interior_point(&self) -> Point {
+        Poly::from(self.clone()).interior_point()
+    }
+
+    /// The point inside the ring farthest from its boundary.
+    ///
+    /// See [`Poly::pole_of_inaccessibility`].
+    pub fn pole_of_inaccessibility(&self, precision: f64) -> Point {
+        Poly::from(self.clone()).pole_of_inaccessibility(precision)
+    }
+}
+
+/// Centroids and label points
+impl Poly {
+    /// The area-weighted center of the polygon, with holes subtracted.
+    pub fn centroid(&self) -> Point {
+        let mut sum = CentroidSum::default();
+        sum.add_poly(self);
+        sum.centroid().unwrap_or_else(|| self.rect().center())
+    }
+
+    /// A point guaranteed to be inside the polygon, not on its boundary or in
+    /// a hole.
+    ///
+    /// This is the middle of the widest interior span along a horizontal line
+    /// through the middle of the polygon. Polygons with zero area fall back to
+    /// a vertex of the exterior.
+    pub fn interior_point(&self) -> Point {
+        match widest_span(self) {
+            Some(span) => span.midpoint(),
+            None => Line::new(self.exterior().points()).interior_point(),
+        }
+    }
+
+    /// The point inside the polygon farthest from its boundary, to within
+    /// `precision`.
+    ///
+    /// This uses the polylabel algorithm, with the distance to the boundary
+    /// computed through the rings' segment indexes.
+    pub fn pole_of_inaccessibility(&self, precision: f64) -> Point {
+        pole_of_inaccessibility(self, precision).0
+    }
+}
+
+/// Centroids and label points
+impl Geom {
+    /// The center of mass of the geometry.
+    ///
+    /// Only the highest-dimension parts of the geometry count: in a
+    /// collection of polygons and lines, the lines are ignored. Empty
+    /// geometries have the center of their rect as centroid.
+    pub fn centroid(&self) -> Point {
+        let mut sum = CentroidSum::default();
+        sum.add_geom(self);
+        sum.centroid().unwrap_or_else(|| self.rect().center())
+    }
+
+    /// A point guaranteed to be on the geometry.
+    ///
+    /// For polygonal geometries, this is inside the polygon with the widest
+    /// interior span, see [`Poly::interior_point`]. For lines it is a vertex,
+    /// preferring interior vertices; for points it is the point closest to
+    /// the centroid.
+    pub fn interior_point(&self) -> Point {
+        let mut polys = Vec::new();
+        collect_polys(self, &mut polys);
+        let span = polys
+            .iter()
+            .filter_map(widest_span)
+            .max_by(|a, b| a.width().total_cmp(&b.width()));
+        if let Some(span) = span {
+            return span.midpoint();
+        }
+        let mut nearest = NearestVertex::new(self.centroid());
+        nearest.add_geom(self);
+        nearest.point.unwrap_or_else(|| self.rect().center())
+    }
+
+    /// The point inside the geometry farthest from its boundary, to within
+    /// `precision`.
+    ///
+    /// For multipolygons, this is the best pole among all polygons.
+    /// Geometries without polygons fall back to [`Geom::interior_point`].
+    pub fn pole_of_inaccessibility(&self, precision: f64) -> Point {
+        let mut polys = Vec::new();
+        collect_polys(self, &mut polys);
+        polys
+            .iter()
+            .map(|poly| pole_of_inaccessibility(poly, precision))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(point, _)| point)
+            .unwrap_or_else(|| self.interior_point())
+    }
+}
+
+fn collect_polys(geom: &Geom, polys: &mut Vec<Poly>) {
+    match geom.geom_type() {
+        GeomType::Polygon => polys.extend(geom.poly()),
+        GeomType::MultiPolygon => polys.extend((0..geom.num_polys()).filter_map(|i| geom.poly_at(i))),
+        GeomType::GeometryCollection => {
+            for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                collect_polys(&child, polys);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Running weighted sums for each dimension of a geometry.
+#[derive(Default)]
+struct CentroidSum {
+    area: WeightedSum,
+    length: WeightedSum,
+    points: WeightedSum,
+}
+
+#[derive(Default)]
+struct WeightedSum {
+    x: f64,
+    y: f64,
+    weight: f64,
+}
+
+impl WeightedSum {
+    fn add(&mut self, point: Point, weight: f64) {
+        self.x += point.x * weight;
+        self.y += point.y * weight;
+        self.weight += weight;
+    }
+
+    fn mean(&self) -> Option<Point> {
+        (self.weight != 0.).then(|| Point::new(self.x / self.weight, self.y / self.weight))
+    }
+}
+
+impl CentroidSum {
+    fn add_point(&mut self, point: Point) {
+        self.points.add(point, 1.);
+    }
+
+    fn add_line(&mut self, points: &[Point]) {
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let mid = Point::new((a.x + b.x) / 2., (a.y + b.y) / 2.);
+            self.length.add(mid, a.distance(b));
+        }
+        for &point in points {
+            self.add_point(point);
+        }
+    }
+
+    /// Add a ring's area, positively for exteriors (`sign = 1.`) and
+    /// negatively for holes (`sign = -1.`), whatever its winding.
+    fn add_ring(&mut self, vertices: &[Point], sign: f64) {
+        if let Some(&origin) = vertices.first() {
+            // Accumulate relative to the first vertex to limit precision loss
+            // far from the origin.
+            let (mut area2, mut cx, mut cy) = (0., 0., 0.);
+            for (i, &a) in vertices.iter().enumerate() {
+                let b = vertices[(i + 1) % vertices.len()];
+                let (ax, ay) = (a.x - origin.x, a.y - origin.y);
+                let (bx, by) = (b.x - origin.x, b.y - origin.y);
+                let cross = ax * by - bx * ay;
+                area2 += cross;
+                cx += (ax + bx) * cross;
+                cy += (ay + by) * cross;
+            }
+            if area2 != 0. {
+                let center = Point::new(origin.x + cx / (3. * area2), origin.y + cy / (3. * area2));
+                self.area.add(center, sign * area2.abs() / 2.);
+            }
+            let mut closed = vertices.to_vec();
+            closed.push(origin);
+            self.add_line(&closed);
+        }
+    }
+
+    fn add_poly(&mut self, poly: &Poly) {
+        self.add_ring(poly.exterior().vertices(), 1.);
+        for hole in poly.holes() {
+            self.add_ring(hole.vertices(), -1.);
+        }
+    }
+
+    fn add_geom(&mut self, geom: &Geom) {
+        if geom.is_empty() {
+            return;
+        }
+        match geom.geom_type() {
+            GeomType::Point => self.add_point(geom.point()),
+            GeomType::LineString => self.add_line(geom.line().expect("LineString has a line").points()),
+            GeomType::Polygon => self.add_poly(&geom.poly().expect("Polygon has a poly")),
+            GeomType::MultiPoint => {
+                for point in (0..geom.num_points()).filter_map(|i| geom.point_at(i)) {
+                    self.add_point(point);
+                }
+            }
+            GeomType::MultiLineString => {
+                for line in (0..geom.num_lines()).filter_map(|i| geom.line_at(i)) {
+                    self.add_line(line.points());
+                }
+            }
+            GeomType::MultiPolygon => {
+                for poly in (0..geom.num_polys()).filter_map(|i| geom.poly_at(i)) {
+                    self.add_poly(&poly);
+                }
+            }
+            GeomType::GeometryCollection => {
+                for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                    self.add_geom(&child);
+                }
+            }
+        }
+    }
+
+    /// The centroid of the highest dimension with any weight.
+    fn centroid(&self) -> Option<Point> {
+        self.area
+            .mean()
+            .or_else(|| self.length.mean())
+            .or_else(|| self.points.mean())
+    }
+}
+
+/// Tracks the vertex closest to a target point, preferring vertices which
+/// are not line endpoints.
+struct NearestVertex {
+    target: Point,
+    point: Option<Point>,
+    distance: f64,
+    interior: bool,
+}
+
+impl NearestVertex {
+    fn new(target: Point) -> NearestVertex {
+        NearestVertex {
+            target,
+            point: None,
+            distance: f64::INFINITY,
+            interior: false,
+        }
+    }
+
+    fn add(&mut self, point: Point, interior: bool) {
+        let distance = point.distance(self.target);
+        if (interior && !self.interior) || (interior == self.interior && distance < self.distance) {
+            self.point = Some(point);
+            self.distance = distance;
+            self.interior = interior;
+        }
+    }
+
+    fn add_line(&mut self, points: &[Point]) {
+        for (i, &point) in points.iter().enumerate() {
+            self.add(point, i != 0 && i + 1 != points.len());
+        }
+    }
+
+    fn add_geom(&mut self, geom: &Geom) {
+        if geom.is_empty() {
+            return;
+        }
+        match geom.geom_type() {
+            GeomType::Point => self.add(geom.point(), false),
+            GeomType::LineString => self.add_line(geom.line().expect("LineString has a line").points()),
+            GeomType::MultiPoint => {
+                for point in (0..geom.num_points()).filter_map(|i| geom.point_at(i)) {
+                    self.add(point, false);
+                }
+            }
+            GeomType::MultiLineString => {
+                for line in (0..geom.num_lines()).filter_map(|i| geom.line_at(i)) {
+                    self.add_line(line.points());
+                }
+            }
+            GeomType::Polygon | GeomType::MultiPolygon => {
+                // only reached for zero-area polygons
+                for poly in geom.poly().into_iter().chain((0..geom.num_polys()).filter_map(|i| geom.poly_at(i))) {
+                    self.add_line(poly.exterior().points());
+                }
+            }
+            GeomType::GeometryCollection => {
+                for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                    self.add_geom(&child);
+                }
+            }
+        }
+    }
+}
+
+/// A horizontal interval inside a polygon.
+struct Span {
+    y: f64,
+    x0: f64,
+    x1: f64,
+}
+
+impl Span {
+    fn width(&self) -> f64 {
+        self.x1 - self.x0
+    }
+
+    fn midpoint(&self) -> Point {
+        Point::new((self.x0 + self.x1) / 2., self.y)
+    }
+}
+
+/// The widest interior span of the polygon along a horizontal scan line near
+/// its middle.
+///
+/// The scan line is placed halfway between two vertex y coordinates, so it
+/// never passes through a vertex and every crossing is a proper one.
+fn widest_span(poly: &Poly) -> Option<Span> {
+    let rings: Vec<Ring> = std::iter::once(poly.exterior()).chain(poly.holes()).collect();
+    let rect = poly.rect();
+    let center = (rect.min().y + rect.max().y) / 2.;
+    let (mut below, mut above) = (rect.min().y, rect.max().y);
+    for point in rings.iter().flat_map(|ring| ring.vertices()) {
+        if point.y <= center && point.y > below {
+            below = point.y;
+        } else if point.y > center && point.y < above {
+            above = point.y;
+        }
+    }
+    let y = (below + above) / 2.;
+
+    let mut crossings: Vec<f64> = Vec::new();
+    for ring in &rings {
+        let vertices = ring.vertices();
+        for (i, &a) in vertices.iter().enumerate() {
+            let b = vertices[(i + 1) % vertices.len()];
+            if (a.y > y) != (b.y > y) {
+                crossings.push(a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x));
+            }
+        }
+    }
+    crossings.sort_by(f64::total_cmp);
+    crossings
+        .chunks_exact(2)
+        .map(|pair| Span {
+            y,
+            x0: pair[0],
+            x1: pair[1],
+        })
+        .filter(|span| span.width() > 0.)
+        .max_by(|a, b| a.width().total_cmp(&b.width()))
+}
+
+/// The pole of inaccessibility of `poly` and its distance to the boundary.
+fn pole_of_inaccessibility(poly: &Poly, precision: f64) -> (Point, f64) {
+    let rings: Vec<Ring> = std::iter::once(poly.exterior()).chain(poly.holes()).collect();
+    let rect = poly.rect();
+    let (width, height) = (rect.max().x - rect.min().x, rect.max().y - rect.min().y);
+    let cell_size = width.min(height);
+    if cell_size == 0. || !cell_size.is_finite() {
+        return (rect.min(), 0.);
+    }
+    let precision = precision.max(cell_size * f64::EPSILON);
+    let cell = |center: Point, half: f64| Cell::new(center, half, poly, &rings);
+
+    let mut queue = BinaryHeap::new();
+    let half = cell_size / 2.;
+    let mut x = rect.min().x;
+    while x < rect.max().x {
+        let mut y = rect.min().y;
+        while y < rect.max().y {
+            queue.push(cell(Point::new(x + half, y + half), half));
+            y += cell_size;
+        }
+        x += cell_size;
+    }
+
+    let mut best = cell(poly.centroid(), 0.);
+    let center = cell(rect.center(), 0.);
+    if center.distance > best.distance {
+        best = center;
+    }
+
+    while let Some(next) = queue.pop() {
+        if next.distance > best.distance {
+            best = next.clone();
+        }
+        if next.max - best.distance <= precision {
+            continue;
+        }
+        let half = next.half / 2.;
+        for (dx, dy) in [(-1., -1.), (1., -1.), (-1., 1.), (1., 1.)] {
+            let center = Point::new(next.center.x + dx * half, next.center.y + dy * half);
+            queue.push(cell(center, half));
+        }
+    }
+    (best.center, best.distance)
+}
+
+/// A square search cell for the polylabel algorithm.
+#[derive(Clone)]
+struct Cell {
+    center: Point,
+    half: f64,
+    /// Signed distance from the center to the polygon boundary, negative
+    /// outside the polygon.
+    distance: f64,
+    /// Upper bound of the distance for any point in the cell.
+    max: f64,
+}
+
+impl Cell {
+    fn new(center: Point, half: f64, poly: &Poly, rings: &[Ring]) -> Cell {
+        let distance = rings
+            .iter()
+            .filter_map(|ring| ring.nearest_segment_to_point(center))
+            .map(|(_, _, distance)| distance)
+            .fold(f64::INFINITY, f64::min);
+        let distance = if poly.intersects_point(center) {
+            distance
+        } else {
+            -distance
+        };
+        Cell {
+            center,
+            half,
+            distance,
+            max: distance + half * SQRT_2,
+        }
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max.total_cmp(&other.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::square;
+    use crate::{Geom, Line, Point, Poly, Ring};
+
+    fn ring(points: &[(f64, f64)]) -> Ring {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Ring::new(&points)
+    }
+
+    fn assert_close(a: Point, b: Point) {
+        assert!((a.x - b.x).abs() < 1e-9 && (a.y - b.y).abs() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    /// A "C" shape whose centroid falls in its mouth.
+    fn c_shape() -> Ring {
+        ring(&[
+            (0., 0.),
+            (10., 0.),
+            (10., 2.),
+            (2., 2.),
+            (2., 8.),
+            (10., 8.),
+            (10., 10.),
+            (0., 10.),
+            (0., 0.),
+        ])
+    }
+
+    #[test]
+    fn centroids() {
+        let square = square(0., 0., 4.);
+        assert_close(square.centroid(), Point::new(2., 2.));
+        let hole = ring(&[(0., 0.), (2., 0.), (2., 4.), (0., 4.), (0., 0.)]);
+        let poly = Poly::new(&square, &[hole]);
+        assert_close(poly.centroid(), Point::new(3., 2.));
+
+        let line = Line::new(&[Point::new(0., 0.), Point::new(2., 0.), Point::new(2., 1.)]);
+        assert_close(line.centroid(), Point::new(4. / 3., 1. / 6.));
+
+        let mixed = Geom::new_geometrycollection(&[square.geom(), Point::new(100., 100.).geom()]);
+        assert_close(mixed.centroid(), Point::new(2., 2.));
+    }
+
+    #[test]
+    fn interior_points_are_inside() {
+        let c = c_shape();
+        let centroid = c.centroid();
+        assert!(!c.geom().intersects_xy(centroid.x, centroid.y));
+        let interior = c.interior_point();
+        assert!(c.geom().intersects_xy(interior.x, interior.y));
+
+        let line = Line::new(&[Point::new(0., 0.), Point::new(1., 5.), Point::new(2., 0.)]);
+        assert_eq!(line.interior_point(), Point::new(1., 5.));
+    }
+
+    #[test]
+    fn pole_of_inaccessibility() {
+        let c = c_shape();
+        let pole = c.pole_of_inaccessibility(0.01);
+        assert!(c.geom().intersects_xy(pole.x, pole.y));
+        // the widest part of the C is its 2 unit thick spine or its arms
+        let (_, _, distance) = c.nearest_segment_to_point(pole).unwrap();
+        assert!(distance > 0.99, "{pole:?} is {distance} from the boundary");
+
+        let square = square(0., 0., 4.);
+        let pole = square.geom().pole_of_inaccessibility(1e-6);
+        assert!((pole.x - 2.).abs() < 1e-3 && (pole.y - 2.).abs() < 1e-3);
+    }
+}
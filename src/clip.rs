@@ -0,0 +1,386 @@
+//! Clipping geometries to a rectangle.
+//!
+//! Lines are clipped segment by segment (Liang-Barsky), so a line which leaves
+//! and re-enters the rect comes back in several pieces. Rings are clipped
+//! against each edge of the rect in turn (Sutherland-Hodgman). A concave ring
+//! which crosses the rect more than once is kept as a single ring, joined by
+//! zero-width spans along the rect's boundary.
+
+use crate::{Geom, GeomType, Line, Point, Poly, Rect, Ring};
+
+/// Clipping
+impl Line {
+    /// The parts of the line inside `rect`, in order along the line.
+    ///
+    /// Parts which only touch the rect at a single point are dropped.
+    pub fn clip_to_rect(&self, rect: Rect) -> Vec<Line> {
+        if !rect.intersects_rect(self.rect()) {
+            return Vec::new();
+        }
+        if rect.contains_rect(self.rect()) {
+            return vec![self.clone()];
+        }
+        let mut lines = Vec::new();
+        let mut current: Vec<Point> = Vec::new();
+        let mut flush = |current: &mut Vec<Point>| {
+            if current.len() >= 2 {
+                lines.push(Line::new(current));
+            }
+            current.clear();
+        };
+        for pair in self.points().windows(2) {
+            let Some((a, b)) = clip_segment(pair[0], pair[1], rect) else {
+                flush(&mut current);
+                continue;
+            };
+            if current.last() != Some(&a) {
+                flush(&mut current);
+                current.push(a);
+            }
+            if b != a {
+                current.push(b);
+            }
+        }
+        flush(&mut current);
+        lines
+    }
+}
+
+/// Clipping
+impl Ring {
+    /// The part of the ring inside `rect`, or `None` if it has no area.
+    ///
+    /// The winding order of the ring is preserved.
+    pub fn clip_to_rect(&self, rect: Rect) -> Option<Ring> {
+        if !rect.intersects_rect(self.rect()) {
+            return None;
+        }
+        if rect.contains_rect(self.rect()) {
+            return Some(self.clone());
+        }
+        let mut points = clip_ring(self.vertices(), rect);
+        if points.len() < 3 {
+            return None;
+        }
+        points.push(points[0]);
+        let ring = Ring::new(&points);
+        (ring.area() > 0.).then_some(ring)
+    }
+}
+
+/// Clipping
+impl Poly {
+    /// The parts of the polygon inside `rect`.
+    ///
+    /// Holes inside the rect are kept as they are and those outside it are
+    /// dropped. Holes which reach the rect's boundary are cut out of the
+    /// clipped exterior, which can split it into several polygons.
+    pub fn clip_to_rect(&self, rect: Rect) -> Vec<Poly> {
+        if rect.contains_rect(self.rect()) {
+            return vec![self.clone()];
+        }
+        let Some(exterior) = self.exterior().clip_to_rect(rect) else {
+            return Vec::new();
+        };
+        let (min, max) = (rect.min(), rect.max());
+        let inside = |ring: &Ring| {
+            let r = ring.rect();
+            r.min().x > min.x && r.min().y > min.y && r.max().x < max.x && r.max().y < max.y
+        };
+        let (holes, cut): (Vec<Ring>, Vec<Ring>) = self.holes().into_iter().partition(inside);
+        let mut polys = vec![Poly::new(&exterior, &holes)];
+        // A clipped hole which runs along the rect's boundary would share
+        // that edge with the clipped exterior, so it is cut out instead.
+        for hole in cut.iter().filter_map(|hole| hole.clip_to_rect(rect)) {
+            let hole = Poly::from(hole);
+            polys = polys
+                .iter()
+                .flat_map(|poly| {
+                    let rest = poly.difference(&hole);
+                    (0..rest.num_polys()).filter_map(move |i| rest.poly_at(i))
+                })
+                .collect();
+        }
+        polys
+    }
+}
+
+/// Clipping
+impl Geom {
+    /// The part of the geometry inside `rect`.
+    ///
+    /// Points keep their type, and become empty if they are outside the rect.
+    /// Linestrings and polygons always become multilinestrings and
+    /// multipolygons, even when they lie inside the rect, because clipping
+    /// can split them. Collections drop the members which are clipped away
+    /// entirely.
+    ///
+    /// Other geometries which lie entirely inside the rect are returned
+    /// unchanged. Otherwise, Z and M coordinates and GeoJSON feature
+    /// properties are dropped.
+    pub fn clip_to_rect(&self, rect: Rect) -> Geom {
+        let single = matches!(self.geom_type(), GeomType::LineString | GeomType::Polygon);
+        if self.is_empty() || (!single && rect.contains_rect(self.rect())) {
+            return self.clone();
+        }
+        match self.geom_type() {
+            GeomType::Point => {
+                if rect.intersects_point(self.point()) {
+                    self.clone()
+                } else {
+                    Geom::new_empty(GeomType::Point)
+                }
+            }
+            GeomType::LineString => {
+                let lines = self.line().map(|line| line.clip_to_rect(rect)).unwrap_or_default();
+                Geom::new_multilinestring(&lines)
+            }
+            GeomType::Polygon => {
+                let polys = self.poly().map(|poly| poly.clip_to_rect(rect)).unwrap_or_default();
+                Geom::new_multipolygon(&polys)
+            }
+            GeomType::MultiPoint => {
+                let points: Vec<Point> = (0..self.num_points())
+                    .filter_map(|i| self.point_at(i))
+                    .filter(|&point| rect.intersects_point(point))
+                    .collect();
+                Geom::new_multipoint(&points)
+            }
+            GeomType::MultiLineString => {
+                let lines: Vec<Line> = (0..self.num_lines())
+                    .filter_map(|i| self.line_at(i))
+                    .flat_map(|line| line.clip_to_rect(rect))
+                    .collect();
+                Geom::new_multilinestring(&lines)
+            }
+            GeomType::MultiPolygon => {
+                let polys: Vec<Poly> = (0..self.num_polys())
+                    .filter_map(|i| self.poly_at(i))
+                    .flat_map(|poly| poly.clip_to_rect(rect))
+                    .collect();
+                Geom::new_multipolygon(&polys)
+            }
+            GeomType::GeometryCollection => {
+                let geoms: Vec<Geom> = (0..self.num_geometries())
+                    .filter_map(|i| self.geometry_at(i))
+                    .map(|geom| geom.clip_to_rect(rect))
+                    .filter(|geom| !geom.is_empty())
+                    .collect();
+                Geom::new_geometrycollection(&geoms)
+            }
+        }
+    }
+}
+
+/// The part of the segment from `a` to `b` inside `rect`, if any.
+fn clip_segment(a: Point, b: Point, rect: Rect) -> Option<(Point, Point)> {
+    let (min, max) = (rect.min(), rect.max());
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (mut t0, mut t1) = (0_f64, 1_f64);
+    for (p, q) in [
+        (-dx, a.x - min.x),
+        (dx, max.x - a.x),
+        (-dy, a.y - min.y),
+        (dy, max.y - a.y),
+    ] {
+        if p == 0. {
+            // parallel to this edge
+            if q < 0. {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0. {
+            if t > t1 {
+                return None;
+            }
+            t0 = t0.max(t);
+        } else {
+            if t < t0 {
+                return None;
+            }
+            t1 = t1.min(t);
+        }
+    }
+    // Keep the original endpoints exactly when they aren't clipped, so that
+    // consecutive segments still join up.
+    let at = |t: f64| match t {
+        0. => a,
+        1. => b,
+        t => Point::new(a.x + t * dx, a.y + t * dy),
+    };
+    Some((at(t0), at(t1)))
+}
+
+/// An edge of the clipping rect, and the half-plane it keeps.
+#[derive(Clone, Copy)]
+enum Edge {
+    Left(f64),
+    Right(f64),
+    Bottom(f64),
+    Top(f64),
+}
+
+impl Edge {
+    fn inside(self, p: Point) -> bool {
+        match self {
+            Edge::Left(x) => p.x >= x,
+            Edge::Right(x) => p.x <= x,
+            Edge::Bottom(y) => p.y >= y,
+            Edge::Top(y) => p.y <= y,
+        }
+    }
+
+    /// Where the segment from `a` to `b` crosses the edge.
+    fn intersection(self, a: Point, b: Point) -> Point {
+        match self {
+            Edge::Left(x) | Edge::Right(x) => Point::new(x, a.y + (x - a.x) / (b.x - a.x) * (b.y - a.y)),
+            Edge::Bottom(y) | Edge::Top(y) => Point::new(a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x), y),
+        }
+    }
+}
+
+/// The vertices of the open ring `vertices` clipped to `rect`, without a
+/// closing point.
+fn clip_ring(vertices: &[Point], rect: Rect) -> Vec<Point> {
+    let (min, max) = (rect.min(), rect.max());
+    let mut output = vertices.to_vec();
+    for edge in [Edge::Left(min.x), Edge::Right(max.x), Edge::Bottom(min.y), Edge::Top(max.y)] {
+        let input = std::mem::take(&mut output);
+        let Some(&last) = input.last() else {
+            break;
+        };
+        let mut prev = last;
+        for &point in &input {
+            if edge.inside(point) {
+                if !edge.inside(prev) {
+                    output.push(edge.intersection(prev, point));
+                }
+                output.push(point);
+            } else if edge.inside(prev) {
+                output.push(edge.intersection(prev, point));
+            }
+            prev = point;
+        }
+    }
+    output.dedup();
+    if output.len() > 1 && output.first() == output.last() {
+        output.pop();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::square;
+    use crate::{Geom, GeomType, Line, Point, Poly, Rect, Ring};
+
+    fn unit_rect() -> Rect {
+        Rect::new(Point::new(0., 0.), Point::new(10., 10.))
+    }
+
+    #[test]
+    fn clip_line() {
+        let line = Line::new(&[
+            Point::new(-5., 5.),
+            Point::new(5., 5.),
+            Point::new(5., 15.),
+            Point::new(8., 15.),
+            Point::new(8., 5.),
+            Point::new(20., 5.),
+        ]);
+        let parts = line.clip_to_rect(unit_rect());
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].points(), &[Point::new(0., 5.), Point::new(5., 5.), Point::new(5., 10.)]);
+        assert_eq!(
+            parts[1].points(),
+            &[Point::new(8., 10.), Point::new(8., 5.), Point::new(10., 5.)]
+        );
+
+        let outside = Line::new(&[Point::new(-5., -5.), Point::new(-1., 20.)]);
+        assert!(outside.clip_to_rect(unit_rect()).is_empty());
+        let corner = Line::new(&[Point::new(-5., 5.), Point::new(5., -5.)]);
+        assert!(corner.clip_to_rect(unit_rect()).is_empty());
+    }
+
+    #[test]
+    fn clip_ring_and_poly() {
+        let ring = square(5., 5., 10.);
+        let clipped = ring.clip_to_rect(unit_rect()).unwrap();
+        assert_eq!(clipped.area(), 25.);
+        assert_eq!(clipped.clockwise(), ring.clockwise());
+        assert!(clipped.eq_topo(&square(5., 5., 5.)));
+
+        assert!(square(20., 20., 1.).clip_to_rect(unit_rect()).is_none());
+        // touches the rect along an edge only
+        assert!(square(10., 0., 5.).clip_to_rect(unit_rect()).is_none());
+
+        let inner = square(1., 1., 2.);
+        assert_eq!(inner.clip_to_rect(unit_rect()), Some(inner));
+
+        let poly = Poly::new(&square(-10., -10., 30.), &[square(2., 2., 2.), square(15., 15., 2.)]);
+        let clipped = poly.clip_to_rect(unit_rect());
+        assert_eq!(clipped.len(), 1);
+        assert!(clipped[0].exterior().eq_topo(&square(0., 0., 10.)));
+        assert_eq!(clipped[0].num_holes(), 1);
+        assert!(Poly::from(square(20., 20., 1.)).clip_to_rect(unit_rect()).is_empty());
+    }
+
+    #[test]
+    fn clip_holes_on_the_boundary() {
+        let area = |poly: &Poly| poly.exterior().area() - poly.holes().iter().map(Ring::area).sum::<f64>();
+        // a hole crossing the right edge of the rect is cut out of the exterior
+        let poly = Poly::new(&square(-10., -10., 30.), &[square(2., 2., 2.), square(8., 2., 4.)]);
+        let clipped = poly.clip_to_rect(unit_rect());
+        assert_eq!(clipped.len(), 1);
+        assert_eq!(area(&clipped[0]), 100. - 4. - 8.);
+        assert_eq!(clipped[0].num_holes(), 1);
+        assert_eq!(clipped[0].validate(), vec![]);
+
+        // a hole crossing the whole rect splits it in two
+        let hole = Ring::new(&[
+            Point::new(-2., 4.),
+            Point::new(12., 4.),
+            Point::new(12., 6.),
+            Point::new(-2., 6.),
+            Point::new(-2., 4.),
+        ]);
+        let poly = Poly::new(&square(-10., -10., 30.), &[hole]);
+        let clipped = poly.clip_to_rect(unit_rect());
+        assert_eq!(clipped.len(), 2);
+        for part in &clipped {
+            assert_eq!(area(part), 40.);
+            assert_eq!(part.validate(), vec![]);
+        }
+        let clipped = poly.geom().clip_to_rect(unit_rect());
+        assert_eq!(clipped.geom_type(), GeomType::MultiPolygon);
+        assert_eq!(clipped.num_polys(), 2);
+    }
+
+    #[test]
+    fn clip_geometry() {
+        let rect = unit_rect();
+        assert!(Point::new(20., 0.).geom().clip_to_rect(rect).is_empty());
+
+        let line = Line::new(&[Point::new(-5., 5.), Point::new(5., 5.)]).geom();
+        let clipped = line.clip_to_rect(rect);
+        assert_eq!(clipped.geom_type(), GeomType::MultiLineString);
+        assert_eq!(clipped.num_lines(), 1);
+        // the same type whether or not anything was clipped away
+        let inside = Line::new(&[Point::new(1., 5.), Point::new(5., 5.)]).geom();
+        assert_eq!(inside.clip_to_rect(rect).geom_type(), GeomType::MultiLineString);
+        let inside = Poly::from(square(1., 1., 2.)).geom();
+        assert_eq!(inside.clip_to_rect(rect).geom_type(), GeomType::MultiPolygon);
+
+        let collection = Geom::new_geometrycollection(&[
+            Point::new(20., 0.).geom(),
+            Geom::new_multipoint(&[Point::new(1., 1.), Point::new(-1., 1.)]),
+            Poly::from(square(5., 5., 10.)).geom(),
+        ]);
+        let clipped = collection.clip_to_rect(rect);
+        assert_eq!(clipped.num_geometries(), 2);
+        assert_eq!(clipped.geometry_at(0).unwrap().num_points(), 1);
+        assert_eq!(clipped.rect(), Rect::new(Point::new(1., 1.), Point::new(10., 10.)));
+    }
+}
@@ -0,0 +1,311 @@
+//! Minimum distance between geometries.
+//!
+//! Geometries are broken into points and the boundaries of their lines and
+//! polygons. For each pair of boundaries, the segments of the smaller one are
+//! looked up in the segment index of the larger one, and pairs whose bounding
+//! rects are farther apart than the best distance so far are skipped.
+
+use crate::{visitors::NearestToSegment, Geom, GeomType, Line, Point, Rect, Ring, Segment};
+
+/// Distance
+impl Geom {
+    /// The euclidean distance between the closest points of the two
+    /// geometries, which is zero if they intersect.
+    ///
+    /// Returns infinity if either geometry is empty.
+    pub fn distance(&self, other: &Geom) -> f64 {
+        if self.is_empty() || other.is_empty() {
+            return f64::INFINITY;
+        }
+        if self.intersects(other) {
+            return 0.;
+        }
+        nearest(&parts(self), &parts(other), 0.).map_or(f64::INFINITY, |(distance, _, _)| distance)
+    }
+
+    /// The closest points on `self` and `other`, which are the same point if
+    /// the geometries intersect.
+    ///
+    /// Returns `None` if either geometry is empty.
+    pub fn closest_points(&self, other: &Geom) -> Option<(Point, Point)> {
+        if self.is_empty() || other.is_empty() {
+            return None;
+        }
+        let (a, b) = (parts(self), parts(other));
+        let (distance, p, q) = nearest(&a, &b, 0.)?;
+        if distance > 0. && self.intersects(other) {
+            // No boundaries touch, so each part lies entirely inside or
+            // outside the other geometry, and any vertex of a part inside is
+            // shared.
+            let inside = |parts: &[Part], geom: &Geom| {
+                parts.iter().map(Part::vertex).find(|v| geom.intersects_xy(v.x, v.y))
+            };
+            if let Some(v) = inside(&b, self).or_else(|| inside(&a, other)) {
+                return Some((v, v));
+            }
+        }
+        Some((p, q))
+    }
+
+    /// Whether the geometries are within `distance` of each other.
+    ///
+    /// This stops searching as soon as it finds points close enough, so it is
+    /// cheaper than comparing [`Geom::distance`].
+    pub fn dwithin(&self, other: &Geom, distance: f64) -> bool {
+        if self.is_empty() || other.is_empty() || self.rect().distance_to_rect(other.rect()) > distance {
+            return false;
+        }
+        if self.intersects(other) {
+            return true;
+        }
+        nearest(&parts(self), &parts(other), distance).is_some_and(|(found, _, _)| found <= distance)
+    }
+}
+
+/// A point, or the boundary of a line or polygon.
+pub(crate) enum Part {
+    Point(Point),
+    Line(Line),
+    Ring(Ring),
+}
+
+impl Part {
+    fn rect(&self) -> Rect {
+        match self {
+            Part::Point(point) => point.rect(),
+            Part::Line(line) => line.rect(),
+            Part::Ring(ring) => ring.rect(),
+        }
+    }
+
+    fn vertex(&self) -> Point {
+        match self {
+            Part::Point(point) => *point,
+            Part::Line(line) => line.points()[0],
+            Part::Ring(ring) => ring.points()[0],
+        }
+    }
+
+    pub(crate) fn points(&self) -> &[Point] {
+        match self {
+            Part::Point(point) => std::slice::from_ref(point),
+            Part::Line(line) => line.points(),
+            Part::Ring(ring) => ring.points(),
+        }
+    }
+
+    fn segments(&self) -> Vec<Segment> {
+        match self {
+            Part::Point(_) => Vec::new(),
+            Part::Line(line) => (0..line.num_segments()).filter_map(|i| line.segment(i)).collect(),
+            Part::Ring(ring) => (0..ring.num_segments()).filter_map(|i| ring.segment(i)).collect(),
+        }
+    }
+
+    /// The distance to `point` and the closest point on this part.
+    fn nearest_to_point(&self, point: Point) -> Option<(f64, Point)> {
+        let (segment, _, distance) = match self {
+            Part::Point(p) => return Some((p.distance(point), *p)),
+            Part::Line(line) => line.nearest_segment_to_point(point)?,
+            Part::Ring(ring) => ring.nearest_segment_to_point(point)?,
+        };
+        Some((distance, segment.closest_point(point)))
+    }
+
+    /// The distance to `segment` and the closest points on this part and on
+    /// the segment.
+    fn nearest_to_segment(&self, segment: Segment) -> Option<(f64, Point, Point)> {
+        let mut visitor = NearestToSegment::new(segment);
+        match self {
+            Part::Point(point) => {
+                let closest = segment.closest_point(*point);
+                return Some((closest.distance(*point), *point, closest));
+            }
+            Part::Line(line) => line.nearest_segment(&mut visitor),
+            Part::Ring(ring) => ring.nearest_segment(&mut visitor),
+        }
+        visitor.nearest
+    }
+}
+
+/// Break a geometry into its points and boundaries.
+pub(crate) fn parts(geom: &Geom) -> Vec<Part> {
+    let mut parts = Vec::new();
+    add_parts(geom, &mut parts);
+    parts
+}
+
+fn add_parts(geom: &Geom, parts: &mut Vec<Part>) {
+    if geom.is_empty() {
+        return;
+    }
+    match geom.geom_type() {
+        GeomType::Point => parts.push(Part::Point(geom.point())),
+        GeomType::MultiPoint => {
+            parts.extend((0..geom.num_points()).filter_map(|i| geom.point_at(i)).map(Part::Point))
+        }
+        GeomType::LineString => add_line(geom.line(), parts),
+        GeomType::MultiLineString => {
+            for i in 0..geom.num_lines() {
+                add_line(geom.line_at(i), parts);
+            }
+        }
+        GeomType::Polygon | GeomType::MultiPolygon => {
+            let polys = match geom.geom_type() {
+                GeomType::Polygon => geom.poly().into_iter().collect(),
+                _ => (0..geom.num_polys()).filter_map(|i| geom.poly_at(i)).collect::<Vec<_>>(),
+            };
+            for poly in polys {
+                parts.push(Part::Ring(poly.exterior()));
+                parts.extend(poly.holes().into_iter().map(Part::Ring));
+            }
+        }
+        GeomType::GeometryCollection => {
+            for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                add_parts(&child, parts);
+            }
+        }
+    }
+}
+
+fn add_line(line: Option<Line>, parts: &mut Vec<Part>) {
+    match line {
+        Some(line) if line.num_segments() > 0 => parts.push(Part::Line(line)),
+        Some(line) => parts.extend(line.points().iter().copied().map(Part::Point)),
+        None => {}
+    }
+}
+
+/// The distance from `point` to the nearest of `parts`.
+pub(crate) fn distance_to_point(parts: &[Part], point: Point) -> f64 {
+    let mut best = f64::INFINITY;
+    for part in parts {
+        if part.rect().distance_to_point(point) >= best {
+            continue;
+        }
+        if let Some((distance, _)) = part.nearest_to_point(point) {
+            best = best.min(distance);
+        }
+    }
+    best
+}
+
+/// The closest pair of points between the parts of `a` and of `b`, with
+/// their distance, stopping early once one is found within `stop`.
+fn nearest(a: &[Part], b: &[Part], stop: f64) -> Option<(f64, Point, Point)> {
+    let mut pairs: Vec<(f64, &Part, &Part)> = a
+        .iter()
+        .flat_map(|a| b.iter().map(move |b| (a.rect().distance_to_rect(b.rect()), a, b)))
+        .collect();
+    pairs.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let mut best: Option<(f64, Point, Point)> = None;
+    for (rect_distance, a, b) in pairs {
+        if best.is_some_and(|(distance, _, _)| distance <= rect_distance) {
+            break;
+        }
+        if let Some(found) = nearest_pair(a, b, stop) {
+            if best.is_none_or(|(distance, _, _)| found.0 < distance) {
+                best = Some(found);
+            }
+        }
+        if best.is_some_and(|(distance, _, _)| distance <= stop) {
+            break;
+        }
+    }
+    best
+}
+
+/// The closest pair of points between two parts, with their distance.
+fn nearest_pair(a: &Part, b: &Part, stop: f64) -> Option<(f64, Point, Point)> {
+    match (a, b) {
+        (Part::Point(p), _) => b.nearest_to_point(*p).map(|(distance, q)| (distance, *p, q)),
+        (_, Part::Point(q)) => a.nearest_to_point(*q).map(|(distance, p)| (distance, p, *q)),
+        _ => {
+            // Look up the segments of the smaller part in the index of the
+            // larger one.
+            let (a_segments, b_segments) = (a.segments(), b.segments());
+            let swap = a_segments.len() > b_segments.len();
+            let (queries, indexed) = if swap { (b_segments, a) } else { (a_segments, b) };
+            let mut best: Option<(f64, Point, Point)> = None;
+            for segment in queries {
+                let Some((distance, on_indexed, on_query)) = indexed.nearest_to_segment(segment) else {
+                    continue;
+                };
+                if best.is_none_or(|(best, _, _)| distance < best) {
+                    best = Some(if swap {
+                        (distance, on_indexed, on_query)
+                    } else {
+                        (distance, on_query, on_indexed)
+                    });
+                }
+                if distance <= stop {
+                    break;
+                }
+            }
+            best
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::square;
+    use crate::{Geom, Line, Point, Poly, Segment};
+
+    #[test]
+    fn segment_distances() {
+        let a = Segment::new(Point::new(0., 0.), Point::new(2., 0.));
+        assert_eq!(a.distance_to_segment(Segment::new(Point::new(1., 1.), Point::new(1., 3.))), 1.);
+        assert_eq!(a.distance_to_segment(Segment::new(Point::new(1., -1.), Point::new(1., 1.))), 0.);
+        assert_eq!(a.distance_to_segment(Segment::new(Point::new(5., 4.), Point::new(9., 9.))), 5.);
+    }
+
+    #[test]
+    fn distance_between_geometries() {
+        let zone = Poly::from(square(0., 0., 10.)).geom();
+        let vehicle = Point::new(13., 14.).geom();
+        assert_eq!(zone.distance(&vehicle), 5.);
+        assert_eq!(
+            zone.closest_points(&vehicle),
+            Some((Point::new(10., 10.), Point::new(13., 14.)))
+        );
+        assert!(zone.dwithin(&vehicle, 5.));
+        assert!(!zone.dwithin(&vehicle, 4.9));
+
+        let road = Line::new(&[Point::new(-5., 15.), Point::new(5., 12.), Point::new(20., 15.)]).geom();
+        assert_eq!(zone.distance(&road), 2.);
+        assert_eq!(road.closest_points(&zone), Some((Point::new(5., 12.), Point::new(5., 10.))));
+
+        let far = Geom::new_multipoint(&[Point::new(100., 100.), Point::new(5., -3.)]);
+        assert_eq!(zone.distance(&far), 3.);
+        assert_eq!(zone.closest_points(&far).unwrap().0, Point::new(5., 0.));
+    }
+
+    #[test]
+    fn intersecting_geometries() {
+        let zone = Poly::from(square(0., 0., 10.)).geom();
+        let inside = Poly::from(square(2., 2., 1.)).geom();
+        assert_eq!(zone.distance(&inside), 0.);
+        assert!(zone.dwithin(&inside, 0.));
+        assert_eq!(zone.closest_points(&inside), Some((Point::new(2., 2.), Point::new(2., 2.))));
+        assert_eq!(inside.closest_points(&zone), Some((Point::new(2., 2.), Point::new(2., 2.))));
+
+        let crossing = Line::new(&[Point::new(-5., 5.), Point::new(5., 5.)]).geom();
+        assert_eq!(zone.distance(&crossing), 0.);
+        assert_eq!(zone.closest_points(&crossing), Some((Point::new(0., 5.), Point::new(0., 5.))));
+
+        // only the second point is inside
+        let scattered = Geom::new_multipoint(&[Point::new(100., 100.), Point::new(5., 5.)]);
+        assert_eq!(zone.distance(&scattered), 0.);
+        assert_eq!(zone.closest_points(&scattered), Some((Point::new(5., 5.), Point::new(5., 5.))));
+        assert_eq!(scattered.closest_points(&zone), Some((Point::new(5., 5.), Point::new(5., 5.))));
+
+        // the hole keeps the point out of the polygon
+        let holed = Poly::new(&square(0., 0., 10.), &[square(2., 2., 6.)]).geom();
+        assert_eq!(holed.distance(&Point::new(5., 4.).geom()), 2.);
+
+        assert_eq!(zone.distance(&Geom::new_multipoint(&[])), f64::INFINITY);
+        assert_eq!(zone.closest_points(&Geom::new_multipoint(&[])), None);
+    }
+}
@@ -0,0 +1,769 @@
+//! Polygon triangulation by ear clipping, ported from Mapbox's [earcut].
+//!
+//! Holes are joined to the exterior by bridges, turning the polygon into a
+//! single ring, whose ears are then clipped off one at a time. Large
+//! polygons look for points inside candidate ears through a z-order curve
+//! instead of walking the whole ring. When no ears are left, the ring is
+//! cleaned up and, failing that, split in two along a diagonal, so bad input
+//! still gives a reasonable result.
+//!
+//! [earcut]: https://github.com/mapbox/earcut
+
+use crate::{point::cross, Point, Poly, Ring};
+
+/// Triangles covering a polygon, as vertex and index buffers.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Triangulation {
+    /// The vertices of the exterior followed by those of each hole, without
+    /// closing points.
+    pub vertices: Vec<Point>,
+    /// Three indices into `vertices` for each triangle, counter-clockwise.
+    pub indices: Vec<u32>,
+}
+
+impl Triangulation {
+    pub fn num_triangles(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// The corners of each triangle.
+    pub fn triangles(&self) -> impl Iterator<Item = [Point; 3]> + '_ {
+        self.indices
+            .chunks_exact(3)
+            .map(|t| [0, 1, 2].map(|k| self.vertices[t[k] as usize]))
+    }
+
+    /// The vertices as interleaved `x, y` pairs of `f32`, the usual layout
+    /// for a GPU vertex buffer.
+    pub fn vertex_buffer(&self) -> Vec<f32> {
+        self.vertices
+            .iter()
+            .flat_map(|p| [p.x as f32, p.y as f32])
+            .collect()
+    }
+}
+
+/// Triangulation
+impl Poly {
+    /// Triangles covering the polygon, with holes left uncovered.
+    pub fn triangulate(&self) -> Triangulation {
+        let mut vertices = self.exterior().vertices().to_vec();
+        let mut hole_starts = Vec::new();
+        for hole in self.holes() {
+            hole_starts.push(vertices.len());
+            vertices.extend_from_slice(hole.vertices());
+        }
+        let mut indices = earcut(&vertices, &hole_starts);
+        for t in indices.chunks_exact_mut(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| vertices[t[k] as usize]);
+            if cross(a, b, c) < 0. {
+                t.swap(1, 2);
+            }
+        }
+        Triangulation { vertices, indices }
+    }
+}
+
+/// Triangulation
+impl Ring {
+    /// Triangles covering the area inside the ring.
+    pub fn triangulate(&self) -> Triangulation {
+        Poly::from(self.clone()).triangulate()
+    }
+}
+
+const NONE: usize = usize::MAX;
+
+/// A vertex in a circular doubly linked list, stored in [`Earcut::nodes`].
+struct Node {
+    /// The index of the vertex.
+    i: usize,
+    x: f64,
+    y: f64,
+    prev: usize,
+    next: usize,
+    /// The position on the z-order curve, once indexed.
+    z: Option<u32>,
+    prev_z: usize,
+    next_z: usize,
+    /// Whether the vertex is a hole of a single point.
+    steiner: bool,
+}
+
+struct Earcut {
+    nodes: Vec<Node>,
+    triangles: Vec<u32>,
+    min_x: f64,
+    min_y: f64,
+    /// The scale of coordinates onto the z-order grid, or zero when the
+    /// z-order index is not used.
+    inv_size: f64,
+}
+
+/// Triangulate the polygon with `vertices`, in which holes start at
+/// `hole_starts`, returning three vertex indices per triangle.
+fn earcut(vertices: &[Point], hole_starts: &[usize]) -> Vec<u32> {
+    let mut e = Earcut {
+        nodes: Vec::with_capacity(vertices.len() * 3 / 2),
+        triangles: Vec::new(),
+        min_x: 0.,
+        min_y: 0.,
+        inv_size: 0.,
+    };
+    let outer_len = hole_starts.first().copied().unwrap_or(vertices.len());
+    let Some(mut outer) = e.linked_list(vertices, 0, outer_len, true) else {
+        return e.triangles;
+    };
+    if e.nodes[outer].next == e.nodes[outer].prev {
+        return e.triangles;
+    }
+    if !hole_starts.is_empty() {
+        outer = e.eliminate_holes(vertices, hole_starts, outer);
+    }
+    if vertices.len() > 80 {
+        let outer_points = &vertices[..outer_len];
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        e.min_x = f64::INFINITY;
+        e.min_y = f64::INFINITY;
+        for p in outer_points {
+            e.min_x = e.min_x.min(p.x);
+            e.min_y = e.min_y.min(p.y);
+            max_x = max_x.max(p.x);
+            max_y = max_y.max(p.y);
+        }
+        let size = (max_x - e.min_x).max(max_y - e.min_y);
+        e.inv_size = if size != 0. { 32767. / size } else { 0. };
+    }
+    e.earcut_linked(outer, 0);
+    e.triangles
+}
+
+impl Earcut {
+    fn x(&self, n: usize) -> f64 {
+        self.nodes[n].x
+    }
+
+    fn y(&self, n: usize) -> f64 {
+        self.nodes[n].y
+    }
+
+    fn next(&self, n: usize) -> usize {
+        self.nodes[n].next
+    }
+
+    fn prev(&self, n: usize) -> usize {
+        self.nodes[n].prev
+    }
+
+    /// A circular list of the vertices from `start` to `end`, wound to match
+    /// `clockwise`.
+    fn linked_list(&mut self, vertices: &[Point], start: usize, end: usize, clockwise: bool) -> Option<usize> {
+        let mut last = None;
+        if clockwise == (signed_area(&vertices[start..end]) > 0.) {
+            for (i, p) in vertices.iter().enumerate().take(end).skip(start) {
+                last = Some(self.insert_node(i, p.x, p.y, last));
+            }
+        } else {
+            for (i, p) in vertices.iter().enumerate().take(end).skip(start).rev() {
+                last = Some(self.insert_node(i, p.x, p.y, last));
+            }
+        }
+        if let Some(l) = last {
+            if self.equals(l, self.next(l)) {
+                self.remove_node(l);
+                last = Some(self.next(l));
+            }
+        }
+        last
+    }
+
+    /// Remove duplicate and collinear points between `start` and `end`.
+    fn filter_points(&mut self, start: usize, end: Option<usize>) -> usize {
+        let mut end = end.unwrap_or(start);
+        let mut p = start;
+        loop {
+            let mut again = false;
+            if !self.nodes[p].steiner
+                && (self.equals(p, self.next(p)) || self.area(self.prev(p), p, self.next(p)) == 0.)
+            {
+                self.remove_node(p);
+                p = self.prev(p);
+                end = p;
+                if p == self.next(p) {
+                    break;
+                }
+                again = true;
+            } else {
+                p = self.next(p);
+            }
+            if !again && p == end {
+                break;
+            }
+        }
+        end
+    }
+
+    /// Clip ears off the ring, trying harder on each `pass` when none are
+    /// left.
+    fn earcut_linked(&mut self, ear: usize, pass: u8) {
+        let mut ear = ear;
+        if pass == 0 && self.inv_size != 0. {
+            self.index_curve(ear);
+        }
+        let mut stop = ear;
+        while self.prev(ear) != self.next(ear) {
+            let (prev, next) = (self.prev(ear), self.next(ear));
+            let is_ear = if self.inv_size != 0. {
+                self.is_ear_hashed(ear)
+            } else {
+                self.is_ear(ear)
+            };
+            if is_ear {
+                self.triangles.extend([prev, ear, next].map(|n| self.nodes[n].i as u32));
+                self.remove_node(ear);
+                ear = self.next(next);
+                stop = ear;
+                continue;
+            }
+            ear = next;
+            if ear == stop {
+                match pass {
+                    0 => {
+                        let ear = self.filter_points(ear, None);
+                        self.earcut_linked(ear, 1);
+                    }
+                    1 => {
+                        let filtered = self.filter_points(ear, None);
+                        let ear = self.cure_local_intersections(filtered);
+                        self.earcut_linked(ear, 2);
+                    }
+                    _ => self.split_earcut(ear),
+                }
+                break;
+            }
+        }
+    }
+
+    /// Whether the corner at `ear` is convex with no other vertex inside it.
+    fn is_ear(&self, ear: usize) -> bool {
+        let (a, b, c) = (self.prev(ear), ear, self.next(ear));
+        if self.area(a, b, c) >= 0. {
+            return false;
+        }
+        let mut p = self.next(c);
+        while p != a {
+            if self.in_triangle(a, b, c, p) && self.area(self.prev(p), p, self.next(p)) >= 0. {
+                return false;
+            }
+            p = self.next(p);
+        }
+        true
+    }
+
+    /// [`Earcut::is_ear`], looking only at vertices whose z-order is within
+    /// the range of the triangle's bounding box.
+    fn is_ear_hashed(&self, ear: usize) -> bool {
+        let (a, b, c) = (self.prev(ear), ear, self.next(ear));
+        if self.area(a, b, c) >= 0. {
+            return false;
+        }
+        let (xs, ys) = ([self.x(a), self.x(b), self.x(c)], [self.y(a), self.y(b), self.y(c)]);
+        let min_z = self.z_order(
+            xs.into_iter().fold(f64::INFINITY, f64::min),
+            ys.into_iter().fold(f64::INFINITY, f64::min),
+        );
+        let max_z = self.z_order(
+            xs.into_iter().fold(f64::NEG_INFINITY, f64::max),
+            ys.into_iter().fold(f64::NEG_INFINITY, f64::max),
+        );
+        let blocks = |p: usize| {
+            p != a && p != c && self.in_triangle(a, b, c, p) && self.area(self.prev(p), p, self.next(p)) >= 0.
+        };
+        let z = |p: usize| self.nodes[p].z.unwrap_or(0);
+        let (mut p, mut n) = (self.nodes[ear].prev_z, self.nodes[ear].next_z);
+        while p != NONE && z(p) >= min_z && n != NONE && z(n) <= max_z {
+            if blocks(p) {
+                return false;
+            }
+            p = self.nodes[p].prev_z;
+            if blocks(n) {
+                return false;
+            }
+            n = self.nodes[n].next_z;
+        }
+        while p != NONE && z(p) >= min_z {
+            if blocks(p) {
+                return false;
+            }
+            p = self.nodes[p].prev_z;
+        }
+        while n != NONE && z(n) <= max_z {
+            if blocks(n) {
+                return false;
+            }
+            n = self.nodes[n].next_z;
+        }
+        true
+    }
+
+    /// Clip off small self-intersections, where the segments on either side
+    /// of a vertex cross.
+    fn cure_local_intersections(&mut self, start: usize) -> usize {
+        let mut start = start;
+        let mut p = start;
+        loop {
+            let a = self.prev(p);
+            let b = self.next(self.next(p));
+            if !self.equals(a, b)
+                && self.intersects(a, p, self.next(p), b)
+                && self.locally_inside(a, b)
+                && self.locally_inside(b, a)
+            {
+                self.triangles.extend([a, p, b].map(|n| self.nodes[n].i as u32));
+                let next = self.next(p);
+                self.remove_node(p);
+                self.remove_node(next);
+                p = b;
+                start = b;
+            }
+            p = self.next(p);
+            if p == start {
+                break;
+            }
+        }
+        self.filter_points(p, None)
+    }
+
+    /// Split the ring in two along a valid diagonal and triangulate both
+    /// halves.
+    fn split_earcut(&mut self, start: usize) {
+        let mut a = start;
+        loop {
+            let mut b = self.next(self.next(a));
+            while b != self.prev(a) {
+                if self.nodes[a].i != self.nodes[b].i && self.is_valid_diagonal(a, b) {
+                    let c = self.split_polygon(a, b);
+                    let a = self.filter_points(a, Some(self.next(a)));
+                    let c = self.filter_points(c, Some(self.next(c)));
+                    self.earcut_linked(a, 0);
+                    self.earcut_linked(c, 0);
+                    return;
+                }
+                b = self.next(b);
+            }
+            a = self.next(a);
+            if a == start {
+                break;
+            }
+        }
+    }
+
+    /// Link each hole into the exterior ring, from left to right.
+    fn eliminate_holes(&mut self, vertices: &[Point], hole_starts: &[usize], outer: usize) -> usize {
+        let mut queue = Vec::new();
+        for (k, &start) in hole_starts.iter().enumerate() {
+            let end = hole_starts.get(k + 1).copied().unwrap_or(vertices.len());
+            let Some(list) = self.linked_list(vertices, start, end, false) else {
+                continue;
+            };
+            if list == self.next(list) {
+                self.nodes[list].steiner = true;
+            }
+            queue.push(self.leftmost(list));
+        }
+        queue.sort_by(|&a, &b| self.x(a).total_cmp(&self.x(b)));
+        let mut outer = outer;
+        for hole in queue {
+            outer = self.eliminate_hole(hole, outer);
+            outer = self.filter_points(outer, Some(self.next(outer)));
+        }
+        outer
+    }
+
+    fn eliminate_hole(&mut self, hole: usize, outer: usize) -> usize {
+        let Some(bridge) = self.find_hole_bridge(hole, outer) else {
+            return outer;
+        };
+        let bridge_reverse = self.split_polygon(bridge, hole);
+        let filtered = self.filter_points(bridge, Some(self.next(bridge)));
+        self.filter_points(bridge_reverse, Some(self.next(bridge_reverse)));
+        if outer == bridge {
+            filtered
+        } else {
+            outer
+        }
+    }
+
+    /// A vertex of the exterior which can be joined to `hole` without
+    /// crossing anything, using David Eberly's algorithm.
+    fn find_hole_bridge(&self, hole: usize, outer: usize) -> Option<usize> {
+        let (hx, hy) = (self.x(hole), self.y(hole));
+        let mut qx = f64::NEG_INFINITY;
+        let mut m = None;
+        // Find the segment to the left of the hole point which a ray to the
+        // left hits first.
+        let mut p = outer;
+        loop {
+            let next = self.next(p);
+            if hy <= self.y(p) && hy >= self.y(next) && self.y(next) != self.y(p) {
+                let x = self.x(p) + (hy - self.y(p)) * (self.x(next) - self.x(p)) / (self.y(next) - self.y(p));
+                if x <= hx && x > qx {
+                    qx = x;
+                    if x == hx {
+                        if hy == self.y(p) {
+                            return Some(p);
+                        }
+                        if hy == self.y(next) {
+                            return Some(next);
+                        }
+                    }
+                    m = Some(if self.x(p) < self.x(next) { p } else { next });
+                }
+            }
+            p = next;
+            if p == outer {
+                break;
+            }
+        }
+        let mut m = m?;
+        if hx == qx {
+            return Some(m);
+        }
+        // Look for vertices inside the triangle of the hole point, the
+        // segment intersection and its endpoint, and take the one with the
+        // smallest angle to the ray.
+        let stop = m;
+        let (mx, my) = (self.x(m), self.y(m));
+        let mut tan_min = f64::INFINITY;
+        p = m;
+        loop {
+            let (px, py) = (self.x(p), self.y(p));
+            let (ax, cx) = if hy < my { (hx, qx) } else { (qx, hx) };
+            if hx >= px && px >= mx && hx != px && point_in_triangle(ax, hy, mx, my, cx, hy, px, py) {
+                let tan = (hy - py).abs() / (hx - px);
+                if self.locally_inside(p, hole)
+                    && (tan < tan_min
+                        || (tan == tan_min
+                            && (px > self.x(m) || (px == self.x(m) && self.sector_contains_sector(m, p)))))
+                {
+                    m = p;
+                    tan_min = tan;
+                }
+            }
+            p = self.next(p);
+            if p == stop {
+                break;
+            }
+        }
+        Some(m)
+    }
+
+    /// Whether the sector at `m` contains the sector at `p`, when they are at
+    /// the same point.
+    fn sector_contains_sector(&self, m: usize, p: usize) -> bool {
+        self.area(self.prev(m), m, self.prev(p)) < 0. && self.area(self.next(p), m, self.next(m)) < 0.
+    }
+
+    /// Sort the ring's nodes by z-order into the `prev_z`/`next_z` list.
+    fn index_curve(&mut self, start: usize) {
+        let mut order = Vec::new();
+        let mut p = start;
+        loop {
+            if self.nodes[p].z.is_none() {
+                self.nodes[p].z = Some(self.z_order(self.x(p), self.y(p)));
+            }
+            order.push(p);
+            p = self.next(p);
+            if p == start {
+                break;
+            }
+        }
+        order.sort_by_key(|&p| self.nodes[p].z);
+        for (k, &p) in order.iter().enumerate() {
+            self.nodes[p].prev_z = if k == 0 { NONE } else { order[k - 1] };
+            self.nodes[p].next_z = order.get(k + 1).copied().unwrap_or(NONE);
+        }
+    }
+
+    /// The position of the point on a z-order curve over the bounding box.
+    fn z_order(&self, x: f64, y: f64) -> u32 {
+        let spread = |v: f64| {
+            let mut v = v as u32;
+            v = (v | (v << 8)) & 0x00FF00FF;
+            v = (v | (v << 4)) & 0x0F0F0F0F;
+            v = (v | (v << 2)) & 0x33333333;
+            (v | (v << 1)) & 0x55555555
+        };
+        spread((x - self.min_x) * self.inv_size) | (spread((y - self.min_y) * self.inv_size) << 1)
+    }
+
+    fn leftmost(&self, start: usize) -> usize {
+        let mut p = start;
+        let mut leftmost = start;
+        loop {
+            if self.x(p) < self.x(leftmost) || (self.x(p) == self.x(leftmost) && self.y(p) < self.y(leftmost)) {
+                leftmost = p;
+            }
+            p = self.next(p);
+            if p == start {
+                break;
+            }
+        }
+        leftmost
+    }
+
+    /// Whether `a` and `b` can be joined without crossing the ring, through
+    /// its inside.
+    fn is_valid_diagonal(&self, a: usize, b: usize) -> bool {
+        let bi = self.nodes[b].i;
+        self.nodes[self.next(a)].i != bi
+            && self.nodes[self.prev(a)].i != bi
+            && !self.intersects_polygon(a, b)
+            && ((self.locally_inside(a, b)
+                && self.locally_inside(b, a)
+                && self.middle_inside(a, b)
+                && (self.area(self.prev(a), a, self.prev(b)) != 0. || self.area(a, self.prev(b), b) != 0.))
+                || (self.equals(a, b)
+                    && self.area(self.prev(a), a, self.next(a)) > 0.
+                    && self.area(self.prev(b), b, self.next(b)) > 0.))
+    }
+
+    /// Twice the signed area of the triangle, positive when clockwise in
+    /// earcut's convention.
+    fn area(&self, p: usize, q: usize, r: usize) -> f64 {
+        (self.y(q) - self.y(p)) * (self.x(r) - self.x(q)) - (self.x(q) - self.x(p)) * (self.y(r) - self.y(q))
+    }
+
+    fn equals(&self, a: usize, b: usize) -> bool {
+        self.x(a) == self.x(b) && self.y(a) == self.y(b)
+    }
+
+    fn in_triangle(&self, a: usize, b: usize, c: usize, p: usize) -> bool {
+        point_in_triangle(self.x(a), self.y(a), self.x(b), self.y(b), self.x(c), self.y(c), self.x(p), self.y(p))
+    }
+
+    /// Whether the segments `p1 q1` and `p2 q2` intersect.
+    fn intersects(&self, p1: usize, q1: usize, p2: usize, q2: usize) -> bool {
+        let sign = |v: f64| if v > 0. { 1 } else if v < 0. { -1 } else { 0 };
+        let o1 = sign(self.area(p1, q1, p2));
+        let o2 = sign(self.area(p1, q1, q2));
+        let o3 = sign(self.area(p2, q2, p1));
+        let o4 = sign(self.area(p2, q2, q1));
+        (o1 != o2 && o3 != o4)
+            || (o1 == 0 && self.on_segment(p1, p2, q1))
+            || (o2 == 0 && self.on_segment(p1, q2, q1))
+            || (o3 == 0 && self.on_segment(p2, p1, q2))
+            || (o4 == 0 && self.on_segment(p2, q1, q2))
+    }
+
+    /// Whether `q` is within the bounding box of `p` and `r`, for collinear
+    /// points.
+    fn on_segment(&self, p: usize, q: usize, r: usize) -> bool {
+        self.x(q) <= self.x(p).max(self.x(r))
+            && self.x(q) >= self.x(p).min(self.x(r))
+            && self.y(q) <= self.y(p).max(self.y(r))
+            && self.y(q) >= self.y(p).min(self.y(r))
+    }
+
+    /// Whether the diagonal `a b` crosses any segment of the ring.
+    fn intersects_polygon(&self, a: usize, b: usize) -> bool {
+        let (ai, bi) = (self.nodes[a].i, self.nodes[b].i);
+        let mut p = a;
+        loop {
+            let next = self.next(p);
+            let (pi, ni) = (self.nodes[p].i, self.nodes[next].i);
+            if pi != ai && ni != ai && pi != bi && ni != bi && self.intersects(p, next, a, b) {
+                return true;
+            }
+            p = next;
+            if p == a {
+                return false;
+            }
+        }
+    }
+
+    /// Whether the diagonal `a b` starts into the inside of the ring at `a`.
+    fn locally_inside(&self, a: usize, b: usize) -> bool {
+        let (prev, next) = (self.prev(a), self.next(a));
+        if self.area(prev, a, next) < 0. {
+            self.area(a, b, next) >= 0. && self.area(a, prev, b) >= 0.
+        } else {
+            self.area(a, b, prev) < 0. || self.area(a, next, b) < 0.
+        }
+    }
+
+    /// Whether the middle of the diagonal `a b` is inside the ring.
+    fn middle_inside(&self, a: usize, b: usize) -> bool {
+        let (px, py) = ((self.x(a) + self.x(b)) / 2., (self.y(a) + self.y(b)) / 2.);
+        let mut inside = false;
+        let mut p = a;
+        loop {
+            let next = self.next(p);
+            let (x0, y0, x1, y1) = (self.x(p), self.y(p), self.x(next), self.y(next));
+            if (y0 > py) != (y1 > py) && y1 != y0 && px < (x1 - x0) * (py - y0) / (y1 - y0) + x0 {
+                inside = !inside;
+            }
+            p = next;
+            if p == a {
+                return inside;
+            }
+        }
+    }
+
+    /// Link `a` to `b` with a diagonal, splitting the ring in two. Returns
+    /// the copy of `b` on the other ring, which is linked to a copy of `a`.
+    fn split_polygon(&mut self, a: usize, b: usize) -> usize {
+        let a2 = self.new_node(self.nodes[a].i, self.x(a), self.y(a));
+        let b2 = self.new_node(self.nodes[b].i, self.x(b), self.y(b));
+        let (an, bp) = (self.next(a), self.prev(b));
+        self.nodes[a].next = b;
+        self.nodes[b].prev = a;
+        self.nodes[a2].next = an;
+        self.nodes[an].prev = a2;
+        self.nodes[b2].next = a2;
+        self.nodes[a2].prev = b2;
+        self.nodes[bp].next = b2;
+        self.nodes[b2].prev = bp;
+        b2
+    }
+
+    fn new_node(&mut self, i: usize, x: f64, y: f64) -> usize {
+        self.nodes.push(Node {
+            i,
+            x,
+            y,
+            prev: NONE,
+            next: NONE,
+            z: None,
+            prev_z: NONE,
+            next_z: NONE,
+            steiner: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn insert_node(&mut self, i: usize, x: f64, y: f64, last: Option<usize>) -> usize {
+        let p = self.new_node(i, x, y);
+        match last {
+            None => {
+                self.nodes[p].prev = p;
+                self.nodes[p].next = p;
+            }
+            Some(last) => {
+                let next = self.next(last);
+                self.nodes[p].next = next;
+                self.nodes[p].prev = last;
+                self.nodes[next].prev = p;
+                self.nodes[last].next = p;
+            }
+        }
+        p
+    }
+
+    /// Unlink `p` from both lists. Its own links are left alone, so it can
+    /// still be used to step to its old neighbors.
+    fn remove_node(&mut self, p: usize) {
+        let Node {
+            prev,
+            next,
+            prev_z,
+            next_z,
+            ..
+        } = self.nodes[p];
+        self.nodes[next].prev = prev;
+        self.nodes[prev].next = next;
+        if prev_z != NONE {
+            self.nodes[prev_z].next_z = next_z;
+        }
+        if next_z != NONE {
+            self.nodes[next_z].prev_z = prev_z;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn point_in_triangle(ax: f64, ay: f64, bx: f64, by: f64, cx: f64, cy: f64, px: f64, py: f64) -> bool {
+    (cx - px) * (ay - py) >= (ax - px) * (cy - py)
+        && (ax - px) * (by - py) >= (bx - px) * (ay - py)
+        && (bx - px) * (cy - py) >= (cx - px) * (by - py)
+}
+
+/// Twice the area of the ring, positive when clockwise in earcut's
+/// convention.
+fn signed_area(points: &[Point]) -> f64 {
+    let mut sum = 0.;
+    let mut j = points.len().wrapping_sub(1);
+    for (i, p) in points.iter().enumerate() {
+        let q = points[j];
+        sum += (q.x - p.x) * (p.y + q.y);
+        j = i;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{point::cross, Point, Poly, Ring};
+
+    fn ring(points: &[(f64, f64)]) -> Ring {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Ring::new(&points)
+    }
+
+    fn area(triangles: &crate::Triangulation) -> f64 {
+        triangles.triangles().map(|[a, b, c]| cross(a, b, c) / 2.).sum()
+    }
+
+    #[test]
+    fn simple_rings() {
+        let square = ring(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)]);
+        let triangles = square.triangulate();
+        assert_eq!(triangles.vertices.len(), 4);
+        assert_eq!(triangles.num_triangles(), 2);
+        assert_eq!(area(&triangles), 1.);
+        assert!(triangles.triangles().all(|[a, b, c]| cross(a, b, c) > 0.));
+        assert_eq!(triangles.vertex_buffer(), [0., 0., 1., 0., 1., 1., 0., 1.]);
+
+        // clockwise and concave
+        let notched = ring(&[(0., 0.), (0., 4.), (2., 2.), (4., 4.), (4., 0.), (0., 0.)]);
+        let triangles = notched.triangulate();
+        assert_eq!(triangles.num_triangles(), 3);
+        assert_eq!(area(&triangles), notched.area());
+        assert!(triangles.triangles().all(|[a, b, c]| cross(a, b, c) > 0.));
+    }
+
+    #[test]
+    fn holes() {
+        let outer = ring(&[(0., 0.), (10., 0.), (10., 10.), (0., 10.), (0., 0.)]);
+        let holes = [
+            ring(&[(2., 2.), (4., 2.), (4., 4.), (2., 4.), (2., 2.)]),
+            ring(&[(6., 6.), (8., 6.), (8., 8.), (6., 8.), (6., 6.)]),
+        ];
+        let poly = Poly::new(&outer, &holes);
+        let triangles = poly.triangulate();
+        assert_eq!(triangles.vertices.len(), 12);
+        // n + 2h - 2 triangles for n vertices and h holes
+        assert_eq!(triangles.num_triangles(), 12 + 2 * 2 - 2);
+        assert_eq!(area(&triangles), 92.);
+        for [a, b, c] in triangles.triangles() {
+            let center = Point::new((a.x + b.x + c.x) / 3., (a.y + b.y + c.y) / 3.);
+            assert!(poly.geom().intersects_xy(center.x, center.y));
+        }
+    }
+
+    #[test]
+    fn large_ring() {
+        // A star with enough points to use the z-order index.
+        let points: Vec<Point> = (0..=200)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * (i % 200) as f64 / 200.;
+                let radius = if i % 2 == 0 { 10. } else { 7. };
+                Point::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        let star = Ring::new(&points);
+        let triangles = star.triangulate();
+        assert_eq!(triangles.num_triangles(), 198);
+        assert!((area(&triangles) - star.area()).abs() < 1e-9);
+    }
+}
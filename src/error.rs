@@ -0,0 +1,79 @@
+//! Errors returned by the checked constructors.
+
+use std::fmt;
+
+use crate::Point;
+
+/// Why a list of points could not be turned into a line or ring.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstructionError {
+    /// Lines need at least 2 points, rings at least 3 vertices besides the
+    /// closing point.
+    TooFewPoints { count: usize, min: usize },
+    /// tg stores point counts as a C `int`.
+    TooManyPoints { count: usize },
+    NonFiniteCoordinate { index: usize, point: Point },
+    /// The first and last points of a ring differ, and closing was not
+    /// requested.
+    UnclosedRing { first: Point, last: Point },
+}
+
+impl fmt::Display for ConstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstructionError::TooFewPoints { count, min } => {
+                write!(f, "too few points: {count}, need at least {min}")
+            }
+            ConstructionError::TooManyPoints { count } => {
+                write!(f, "too many points: {count}, at most {} are supported", i32::MAX)
+            }
+            ConstructionError::NonFiniteCoordinate { index, point } => {
+                write!(f, "non-finite coordinate at index {index}: ({}, {})", point.x, point.y)
+            }
+            ConstructionError::UnclosedRing { first, last } => write!(
+                f,
+                "ring is not closed: starts at ({}, {}) but ends at ({}, {})",
+                first.x, first.y, last.x, last.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConstructionError {}
+
+/// Check that `points` is small enough for tg and all finite.
+pub(crate) fn check_points(points: &[Point]) -> Result<libc::c_int, ConstructionError> {
+    let len = checked_len(points.len())?;
+    match points
+        .iter()
+        .position(|p| !(p.x.is_finite() && p.y.is_finite()))
+    {
+        Some(index) => Err(ConstructionError::NonFiniteCoordinate {
+            index,
+            point: points[index],
+        }),
+        None => Ok(len),
+    }
+}
+
+fn checked_len(count: usize) -> Result<libc::c_int, ConstructionError> {
+    count
+        .try_into()
+        .map_err(|_| ConstructionError::TooManyPoints { count })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checked_len, ConstructionError};
+
+    #[test]
+    fn point_counts_must_fit_a_c_int() {
+        assert_eq!(checked_len(i32::MAX as usize), Ok(i32::MAX));
+        assert_eq!(
+            checked_len(i32::MAX as usize + 1),
+            Err(ConstructionError::TooManyPoints {
+                count: i32::MAX as usize + 1
+            })
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! [Geohashes]: base 32 strings naming cells of a lon/lat grid, where each
+//! character narrows the cell down by a factor of 32.
+//!
+//! Cells whose geohashes share a prefix are nested inside the cell named by
+//! the prefix, so a cover of geohash cells can be looked up by prefix.
+//!
+//! [Geohashes]: https://en.wikipedia.org/wiki/Geohash
+
+use crate::{Geom, GeomType, Location, Point, Rect};
+
+/// The longest geohash [`Point::geohash`] produces. Twelve characters name a
+/// cell a few centimeters across.
+pub const MAX_PRECISION: usize = 12;
+
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// A direction to a neighboring cell.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// All directions, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    /// The number of cells to move east and north.
+    fn offset(self) -> (f64, f64) {
+        match self {
+            Direction::North => (0., 1.),
+            Direction::NorthEast => (1., 1.),
+            Direction::East => (1., 0.),
+            Direction::SouthEast => (1., -1.),
+            Direction::South => (0., -1.),
+            Direction::SouthWest => (-1., -1.),
+            Direction::West => (-1., 0.),
+            Direction::NorthWest => (-1., 1.),
+        }
+    }
+}
+
+/// Geohashes
+impl Point {
+    /// The geohash of the cell containing this lon/lat point, `precision`
+    /// characters long.
+    ///
+    /// Coordinates outside the valid range are clamped to it.
+    ///
+    /// # Panics
+    ///
+    /// If `precision` is greater than [`MAX_PRECISION`].
+    pub fn geohash(self, precision: usize) -> String {
+        assert!(
+            precision <= MAX_PRECISION,
+            "geohash precision {precision} is greater than {MAX_PRECISION}"
+        );
+        let (mut lon, mut lat) = ((-180., 180.), (-90., 90.));
+        let (x, y) = (self.x.clamp(-180., 180.), self.y.clamp(-90., 90.));
+        let mut hash = String::with_capacity(precision);
+        let mut even = true;
+        for _ in 0..precision {
+            let mut index = 0;
+            for _ in 0..5 {
+                let (range, value) = if even { (&mut lon, x) } else { (&mut lat, y) };
+                let mid = (range.0 + range.1) / 2.;
+                index <<= 1;
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+            hash.push(ALPHABET[index] as char);
+        }
+        hash
+    }
+}
+
+/// Geohashes
+impl Rect {
+    /// The lon/lat extent of the cell named by `geohash`, or `None` if it
+    /// contains characters outside the geohash alphabet.
+    ///
+    /// Upper case is accepted. The empty geohash is the whole world.
+    pub fn from_geohash(geohash: &str) -> Option<Rect> {
+        let (mut lon, mut lat) = ((-180., 180.), (-90., 90.));
+        let mut even = true;
+        for c in geohash.bytes() {
+            let index = ALPHABET.iter().position(|&a| a == c.to_ascii_lowercase())?;
+            for bit in (0..5).rev() {
+                let range: &mut (f64, f64) = if even { &mut lon } else { &mut lat };
+                let mid = (range.0 + range.1) / 2.;
+                if index >> bit & 1 == 1 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+        }
+        Some(Rect::new(Point::new(lon.0, lat.0), Point::new(lon.1, lat.1)))
+    }
+}
+
+/// The geohash of the same length as `geohash` next to it in `direction`.
+///
+/// Longitudes wrap around the antimeridian. Returns `None` for cells beyond
+/// the poles, and for invalid geohashes.
+pub fn neighbor(geohash: &str, direction: Direction) -> Option<String> {
+    if geohash.is_empty() || geohash.len() > MAX_PRECISION {
+        return None;
+    }
+    let rect = Rect::from_geohash(geohash)?;
+    let (min, max) = (rect.min(), rect.max());
+    let (dx, dy) = direction.offset();
+    let center = rect.center();
+    let y = center.y + dy * (max.y - min.y);
+    if !(-90. ..=90.).contains(&y) {
+        return None;
+    }
+    let x = (center.x + dx * (max.x - min.x) + 180.).rem_euclid(360.) - 180.;
+    Some(Point::new(x, y).geohash(geohash.len()))
+}
+
+/// The geohashes of the up to eight cells around `geohash`, clockwise from
+/// north.
+///
+/// Cells beyond the poles are left out, and invalid geohashes have no
+/// neighbors.
+pub fn neighbors(geohash: &str) -> Vec<String> {
+    Direction::ALL
+        .iter()
+        .filter_map(|&direction| neighbor(geohash, direction))
+        .collect()
+}
+
+/// The geohashes of length `precision` of every cell which the lon/lat
+/// geometry intersects, in sorted order.
+///
+/// Cells are tested against the geometry itself rather than its bounding
+/// rect, so diagonal lines and thin polygons only cover the cells they pass
+/// through. Cells which a polygon only touches along their edge are left
+/// out. Points and lines also count the cells they only touch, so that one
+/// lying along an edge between cells is still covered.
+///
+/// # Panics
+///
+/// If `precision` is greater than [`MAX_PRECISION`].
+pub fn geohash_cover(geom: &Geom, precision: usize) -> Vec<String> {
+    assert!(
+        precision <= MAX_PRECISION,
+        "geohash precision {precision} is greater than {MAX_PRECISION}"
+    );
+    let mut cells = Vec::new();
+    if !geom.is_empty() {
+        let has_area = matches!(
+            geom.geom_type(),
+            GeomType::Polygon | GeomType::MultiPolygon | GeomType::GeometryCollection
+        );
+        cover(geom, has_area, &mut String::new(), precision, &mut cells);
+    }
+    cells
+}
+
+/// Add the cells of length `precision` under `prefix` which `geom`
+/// intersects.
+fn cover(geom: &Geom, has_area: bool, prefix: &mut String, precision: usize, cells: &mut Vec<String>) {
+    let rect = Rect::from_geohash(prefix).expect("prefix is a valid geohash");
+    if !geom.intersects_rect(rect) {
+        return;
+    }
+    if prefix.len() == precision {
+        let polygonal = matches!(geom.geom_type(), GeomType::Polygon | GeomType::MultiPolygon);
+        let touches = || geom.relate(&rect.geom()).get(Location::Interior, Location::Interior).is_none();
+        if !polygonal || !touches() {
+            cells.push(prefix.clone());
+        }
+        return;
+    }
+    // Skip testing every cell inside a large polygon.
+    let covered = has_area && geom.covers(&rect.geom());
+    for &c in ALPHABET {
+        prefix.push(c as char);
+        if covered {
+            descendants(prefix, precision, cells);
+        } else {
+            cover(geom, has_area, prefix, precision, cells);
+        }
+        prefix.pop();
+    }
+}
+
+/// Add all the cells of length `precision` under `prefix`, in sorted order.
+fn descendants(prefix: &mut String, precision: usize, cells: &mut Vec<String>) {
+    if prefix.len() == precision {
+        cells.push(prefix.clone());
+        return;
+    }
+    for &c in ALPHABET {
+        prefix.push(c as char);
+        descendants(prefix, precision, cells);
+        prefix.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{geohash_cover, neighbor, neighbors, Direction};
+    use crate::{Line, Point, Poly, Rect, Ring};
+
+    #[test]
+    fn encode_and_decode() {
+        let point = Point::new(-5.6, 42.6);
+        assert_eq!(point.geohash(5), "ezs42");
+        let rect = Rect::from_geohash("ezs42").unwrap();
+        assert!(rect.intersects_point(point));
+        assert_eq!(rect.min(), Point::new(-5.625, 42.5830078125));
+        assert_eq!(rect.max(), Point::new(-5.5810546875, 42.626953125));
+        assert_eq!(Rect::from_geohash("EZS42"), Some(rect));
+
+        assert_eq!(Point::new(0., 0.).geohash(0), "");
+        assert_eq!(Rect::from_geohash("").unwrap().max(), Point::new(180., 90.));
+        assert_eq!(Rect::from_geohash("ezs4a"), None);
+        assert_eq!(Point::new(200., 100.).geohash(3), "zzz");
+    }
+
+    #[test]
+    fn neighbor_cells() {
+        assert_eq!(neighbor("ezs42", Direction::North).as_deref(), Some("ezs48"));
+        assert_eq!(neighbor("ezs42", Direction::East).as_deref(), Some("ezs43"));
+        assert_eq!(neighbor("ezs42", Direction::SouthWest).as_deref(), Some("ezefp"));
+        assert_eq!(
+            neighbors("ezs42"),
+            ["ezs48", "ezs49", "ezs43", "ezs41", "ezs40", "ezefp", "ezefr", "ezefx"]
+        );
+        // wraps around the antimeridian, but not over the poles
+        assert_eq!(neighbor("8", Direction::West).as_deref(), Some("x"));
+        assert_eq!(neighbor("b", Direction::North), None);
+        assert_eq!(neighbors("zzz").len(), 5);
+        assert!(neighbors("ezs4a").is_empty());
+    }
+
+    #[test]
+    fn cover_line() {
+        // A diagonal crosses far fewer cells than its bounding rect holds.
+        let line = Line::new(&[Point::new(0.1, 0.1), Point::new(40., 40.)]).geom();
+        let cells = geohash_cover(&line, 2);
+        assert!(cells.iter().all(|cell| line.intersects_rect(Rect::from_geohash(cell).unwrap())));
+        assert!(cells.contains(&Point::new(0.1, 0.1).geohash(2)));
+        assert!(cells.contains(&Point::new(40., 40.).geohash(2)));
+        assert!(cells.len() < 20, "{}", cells.len());
+        let mut sorted = cells.clone();
+        sorted.sort();
+        assert_eq!(cells, sorted);
+    }
+
+    #[test]
+    fn cover_polygon() {
+        let rect = Rect::from_geohash("u4").unwrap();
+        let (min, max) = (rect.min(), rect.max());
+        let ring = Ring::new(&[
+            min,
+            Point::new(max.x, min.y),
+            max,
+            Point::new(min.x, max.y),
+            min,
+        ]);
+        let geom = Poly::from(ring).geom();
+        let cells = geohash_cover(&geom, 3);
+        // The 8 × 4 cells inside, but not the neighbors touching the boundary.
+        assert_eq!(cells.len(), 32);
+        assert!(cells.iter().all(|cell| cell.starts_with("u4")));
+        assert!(geohash_cover(&Line::new(&[]).geom(), 3).is_empty());
+    }
+}
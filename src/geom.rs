@@ -1,14 +1,14 @@
-//! Polygon type and associated code
+//! Geometry type and associated code
 //!
-//! - [ ] Create type
-//! - [ ] Make Send + Sync
-//! - [ ] impl Drop
-//! - [ ] Add GeometryConstructors
+//! - [x] Create type
+//! - [x] Make Send + Sync
+//! - [x] impl Drop
+//! - [x] Add GeometryConstructors
 //! - [ ] Add GeometryConstructorsEx
 //! - [ ] Add GeometryParsing
-//! - [ ] Add GeometryAccessors
+//! - [x] Add GeometryAccessors
 //! - [ ] Add GeometryWriting
-//! - [ ] Add GeometryPredicates
+//! - [x] Add GeometryPredicates
 //! - [ ] Add tg_sys conversions
 //! - [ ] Add PolFuncs
 //! - [ ] Standard traits
@@ -19,7 +19,12 @@ use std::{
     ptr::NonNull,
 };
 
-use tg_sys::{tg_geom, GeometryConstructors};
+use tg_sys::{
+    tg_geom, tg_line, tg_point, tg_poly, GeometryAccessors, GeometryConstructors,
+    GeometryConstructorsEx, GeometryPredicates,
+};
+
+use crate::{GeomType, Line, Point, Poly, Rect};
 
 #[derive(Debug)]
 pub struct Geom {
@@ -62,12 +67,435 @@ impl Geom {
         }
     }
 
+    pub fn new_multipoint(points: &[Point]) -> Geom {
+        let ptr = points.as_ptr() as *const tg_point;
+        let len = points.len().try_into().expect("len must be a valid i32");
+        unsafe { GeometryConstructors::tg_geom_new_multipoint(ptr, len) }.into()
+    }
+
+    pub fn new_multilinestring(lines: &[Line]) -> Geom {
+        let lines: Vec<*const tg_line> = lines.iter().map(|line| line.as_raw() as *const _).collect();
+        let len = lines.len().try_into().expect("len must be a valid i32");
+        unsafe { GeometryConstructors::tg_geom_new_multilinestring(lines.as_ptr(), len) }.into()
+    }
+
+    pub fn new_multipolygon(polys: &[Poly]) -> Geom {
+        let polys: Vec<*const tg_poly> = polys.iter().map(|poly| poly.as_raw() as *const _).collect();
+        let len = polys.len().try_into().expect("len must be a valid i32");
+        unsafe { GeometryConstructors::tg_geom_new_multipolygon(polys.as_ptr(), len) }.into()
+    }
+
+    pub fn new_geometrycollection(geoms: &[Geom]) -> Geom {
+        let geoms: Vec<*const tg_geom> = geoms.iter().map(|geom| geom.as_raw() as *const _).collect();
+        let len = geoms.len().try_into().expect("len must be a valid i32");
+        unsafe { GeometryConstructors::tg_geom_new_geometrycollection(geoms.as_ptr(), len) }.into()
+    }
+
+    /// An empty geometry of the given type.
+    pub(crate) fn new_empty(geom_type: GeomType) -> Geom {
+        let raw = match geom_type {
+            GeomType::Point => unsafe { GeometryConstructorsEx::tg_geom_new_point_empty() },
+            GeomType::LineString => unsafe { GeometryConstructorsEx::tg_geom_new_linestring_empty() },
+            GeomType::Polygon => unsafe { GeometryConstructorsEx::tg_geom_new_polygon_empty() },
+            GeomType::MultiPoint => unsafe { GeometryConstructorsEx::tg_geom_new_multipoint_empty() },
+            GeomType::MultiLineString => unsafe { GeometryConstructorsEx::tg_geom_new_multilinestring_empty() },
+            GeomType::MultiPolygon => unsafe { GeometryConstructorsEx::tg_geom_new_multipolygon_empty() },
+            GeomType::GeometryCollection => unsafe {
+                GeometryConstructorsEx::tg_geom_new_geometrycollection_empty()
+            },
+        };
+        raw.into()
+    }
+
     pub(crate) fn handle_alloc_error() -> ! {
         let layout = Layout::new::<tg_geom>();
         handle_alloc_error(layout);
     }
 }
 
+/// Operations from GeometryAccessors
+impl Geom {
+    pub fn geom_type(&self) -> GeomType {
+        unsafe { GeometryAccessors::tg_geom_typeof(self.as_raw()) }.into()
+    }
+
+    pub fn rect(&self) -> Rect {
+        unsafe { GeometryAccessors::tg_geom_rect(self.as_raw()) }.into()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { GeometryAccessors::tg_geom_is_empty(self.as_raw()) }
+    }
+
+    /// The point of a Point geometry.
+    ///
+    /// For any other geometry type, this is the center of its bounding rect.
+    pub fn point(&self) -> Point {
+        unsafe { GeometryAccessors::tg_geom_point(self.as_raw()) }.into()
+    }
+
+    /// The line of a LineString geometry.
+    pub fn line(&self) -> Option<Line> {
+        let raw = unsafe { GeometryAccessors::tg_geom_line(self.as_raw()) };
+        (!raw.is_null()).then(|| Line::clone_from_raw(raw))
+    }
+
+    /// The polygon of a Polygon geometry.
+    pub fn poly(&self) -> Option<Poly> {
+        let raw = unsafe { GeometryAccessors::tg_geom_poly(self.as_raw()) };
+        (!raw.is_null()).then(|| Poly::clone_from_raw(raw))
+    }
+
+    /// The number of points in a MultiPoint geometry.
+    pub fn num_points(&self) -> usize {
+        unsafe { GeometryAccessors::tg_geom_num_points(self.as_raw()) }
+            .try_into()
+            .expect("tg_geom_num_points should return a valid usize")
+    }
+
+    pub fn point_at(&self, index: usize) -> Option<Point> {
+        (index < self.num_points()).then(|| {
+            unsafe {
+                GeometryAccessors::tg_geom_point_at(
+                    self.as_raw(),
+                    index.try_into().expect("index is a valid c_int"),
+                )
+            }
+            .into()
+        })
+    }
+
+    /// The number of lines in a MultiLineString geometry.
+    pub fn num_lines(&self) -> usize {
+        unsafe { GeometryAccessors::tg_geom_num_lines(self.as_raw()) }
+            .try_into()
+            .expect("tg_geom_num_lines should return a valid usize")
+    }
+
+    pub fn line_at(&self, index: usize) -> Option<Line> {
+        (index < self.num_lines()).then(|| {
+            let raw = unsafe {
+                GeometryAccessors::tg_geom_line_at(
+                    self.as_raw(),
+                    index.try_into().expect("index is a valid c_int"),
+                )
+            };
+            Line::clone_from_raw(raw)
+        })
+    }
+
+    /// The number of polygons in a MultiPolygon geometry.
+    pub fn num_polys(&self) -> usize {
+        unsafe { GeometryAccessors::tg_geom_num_polys(self.as_raw()) }
+            .try_into()
+            .expect("tg_geom_num_polys should return a valid usize")
+    }
+
+    pub fn poly_at(&self, index: usize) -> Option<Poly> {
+        (index < self.num_polys()).then(|| {
+            let raw = unsafe {
+                GeometryAccessors::tg_geom_poly_at(
+                    self.as_raw(),
+                    index.try_into().expect("index is a valid c_int"),
+                )
+            };
+            Poly::clone_from_raw(raw)
+        })
+    }
+
+    /// The number of geometries in a GeometryCollection.
+    pub fn num_geometries(&self) -> usize {
+        unsafe { GeometryAccessors::tg_geom_num_geometries(self.as_raw()) }
+            .try_into()
+            .expect("tg_geom_num_geometries should return a valid usize")
+    }
+
+    pub fn geometry_at(&self, index: usize) -> Option<Geom> {
+        (index < self.num_geometries()).then(|| {
+            let raw = unsafe {
+                GeometryAccessors::tg_geom_geometry_at(
+                    self.as_raw(),
+                    index.try_into().expect("index is a valid c_int"),
+                )
+            };
+            unsafe { GeometryConstructors::tg_geom_clone(raw) }.into()
+        })
+    }
+
+    pub fn has_z(&self) -> bool {
+        unsafe { GeometryAccessors::tg_geom_has_z(self.as_raw()) }
+    }
+
+    pub fn has_m(&self) -> bool {
+        unsafe { GeometryAccessors::tg_geom_has_m(self.as_raw()) }
+    }
+
+    /// The Z coordinate of a Point geometry, or zero for anything else.
+    pub fn z(&self) -> f64 {
+        unsafe { GeometryAccessors::tg_geom_z(self.as_raw()) }
+    }
+
+    /// The M coordinate of a Point geometry, or zero for anything else.
+    pub fn m(&self) -> f64 {
+        unsafe { GeometryAccessors::tg_geom_m(self.as_raw()) }
+    }
+
+    /// The Z and/or M coordinates of a non-Point geometry, interleaved in
+    /// point order.
+    pub fn extra_coords(&self) -> &[f64] {
+        let ptr = unsafe { GeometryAccessors::tg_geom_extra_coords(self.as_raw()) };
+        let len = unsafe { GeometryAccessors::tg_geom_num_extra_coords(self.as_raw()) };
+        if ptr.is_null() || len <= 0 {
+            return &[];
+        }
+        unsafe { std::slice::from_raw_parts(ptr, len as usize) }
+    }
+
+    pub fn memsize(&self) -> usize {
+        unsafe { GeometryAccessors::tg_geom_memsize(self.as_raw()) }
+    }
+}
+
+/// Operations from GeometryPredicates
+impl Geom {
+    pub fn intersects(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_intersects(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn disjoint(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_disjoint(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn contains(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_contains(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn within(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_within(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn covers(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_covers(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn coveredby(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_coveredby(self.as_raw(), other.as_raw()) }
+    }
+
+    pub fn intersects_rect(&self, rect: Rect) -> bool {
+        unsafe { GeometryPredicates::tg_geom_intersects_rect(self.as_raw(), rect.into()) }
+    }
+
+    pub fn intersects_xy(&self, x: f64, y: f64) -> bool {
+        unsafe { GeometryPredicates::tg_geom_intersects_xy(self.as_raw(), x, y) }
+    }
+
+    /// Whether the geometries cover the same space, regardless of how their
+    /// points are ordered or which type they are.
+    ///
+    /// This is `tg_geom_equals`, which tg defines as `within` and `contains`
+    /// both holding, so empty geometries are never topologically equal.
+    pub fn eq_topo(&self, other: &Geom) -> bool {
+        unsafe { GeometryPredicates::tg_geom_equals(self.as_raw(), other.as_raw()) }
+    }
+}
+
+/// Structural equality
+impl Geom {
+    /// Whether the geometries have the same type and the same points in the
+    /// same order, including any Z and M coordinates.
+    ///
+    /// GeoJSON feature properties are not compared.
+    pub fn eq_exact(&self, other: &Geom) -> bool {
+        if self.geom_type() != other.geom_type()
+            || self.is_empty() != other.is_empty()
+            || (self.has_z(), self.has_m()) != (other.has_z(), other.has_m())
+            || self.extra_coords() != other.extra_coords()
+        {
+            return false;
+        }
+        if self.is_empty() {
+            return true;
+        }
+        match self.geom_type() {
+            GeomType::Point => {
+                self.point() == other.point() && self.z() == other.z() && self.m() == other.m()
+            }
+            GeomType::LineString => match (self.line(), other.line()) {
+                (Some(a), Some(b)) => a.points() == b.points(),
+                (a, b) => a.is_none() && b.is_none(),
+            },
+            GeomType::Polygon => self.poly() == other.poly(),
+            GeomType::MultiPoint => {
+                self.num_points() == other.num_points()
+                    && (0..self.num_points()).all(|i| self.point_at(i) == other.point_at(i))
+            }
+            GeomType::MultiLineString => {
+                self.num_lines() == other.num_lines()
+                    && (0..self.num_lines()).all(|i| match (self.line_at(i), other.line_at(i)) {
+                        (Some(a), Some(b)) => a.points() == b.points(),
+                        (a, b) => a.is_none() && b.is_none(),
+                    })
+            }
+            GeomType::MultiPolygon => {
+                self.num_polys() == other.num_polys()
+                    && (0..self.num_polys()).all(|i| self.poly_at(i) == other.poly_at(i))
+            }
+            GeomType::GeometryCollection => {
+                self.num_geometries() == other.num_geometries()
+                    && (0..self.num_geometries()).all(|i| {
+                        match (self.geometry_at(i), other.geometry_at(i)) {
+                            (Some(a), Some(b)) => a.eq_exact(&b),
+                            (a, b) => a.is_none() && b.is_none(),
+                        }
+                    })
+            }
+        }
+    }
+}
+
+/// Coordinate mapping
+impl Geom {
+    /// Create a new geometry of the same shape, with every point passed
+    /// through `f`.
+    ///
+    /// Z and M coordinates are carried over unchanged. GeoJSON feature
+    /// properties are not.
+    pub fn map_points<F: FnMut(Point) -> Point>(&self, mut f: F) -> Geom {
+        self.map_points_dyn(&mut f)
+    }
+
+    fn map_points_dyn(&self, f: &mut dyn FnMut(Point) -> Point) -> Geom {
+        if self.is_empty() {
+            return self.clone();
+        }
+        let coords = self.extra_coords();
+        let ncoords = coords.len().try_into().expect("len must be a valid i32");
+        let coords = coords.as_ptr();
+        let dims = (self.has_z(), self.has_m());
+        let raw = match self.geom_type() {
+            GeomType::Point => {
+                let point = f(self.point()).to_raw();
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_point(point) },
+                    (true, false) => unsafe { GeometryConstructorsEx::tg_geom_new_point_z(point, self.z()) },
+                    (false, true) => unsafe { GeometryConstructorsEx::tg_geom_new_point_m(point, self.m()) },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_point_zm(point, self.z(), self.m())
+                    },
+                }
+            }
+            GeomType::LineString => {
+                let line = self.line().expect("LineString has a line").map_points(&mut *f);
+                let line = line.as_raw();
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_linestring(line) },
+                    (true, false) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_linestring_z(line, coords, ncoords)
+                    },
+                    (false, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_linestring_m(line, coords, ncoords)
+                    },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_linestring_zm(line, coords, ncoords)
+                    },
+                }
+            }
+            GeomType::Polygon => {
+                let poly = self.poly().expect("Polygon has a poly").map_points(&mut *f);
+                let poly = poly.as_raw();
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_polygon(poly) },
+                    (true, false) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_polygon_z(poly, coords, ncoords)
+                    },
+                    (false, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_polygon_m(poly, coords, ncoords)
+                    },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_polygon_zm(poly, coords, ncoords)
+                    },
+                }
+            }
+            GeomType::MultiPoint => {
+                let points: Vec<Point> = (0..self.num_points())
+                    .filter_map(|i| self.point_at(i))
+                    .map(&mut *f)
+                    .collect();
+                let len = points.len().try_into().expect("len must be a valid i32");
+                let points = points.as_ptr() as *const tg_point;
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_multipoint(points, len) },
+                    (true, false) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipoint_z(points, len, coords, ncoords)
+                    },
+                    (false, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipoint_m(points, len, coords, ncoords)
+                    },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipoint_zm(points, len, coords, ncoords)
+                    },
+                }
+            }
+            GeomType::MultiLineString => {
+                let lines: Vec<Line> = (0..self.num_lines())
+                    .filter_map(|i| self.line_at(i))
+                    .map(|line| line.map_points(&mut *f))
+                    .collect();
+                let len = lines.len().try_into().expect("len must be a valid i32");
+                let lines: Vec<*const tg_line> = lines.iter().map(|line| line.as_raw() as *const _).collect();
+                let lines = lines.as_ptr();
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_multilinestring(lines, len) },
+                    (true, false) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multilinestring_z(lines, len, coords, ncoords)
+                    },
+                    (false, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multilinestring_m(lines, len, coords, ncoords)
+                    },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multilinestring_zm(lines, len, coords, ncoords)
+                    },
+                }
+            }
+            GeomType::MultiPolygon => {
+                let polys: Vec<Poly> = (0..self.num_polys())
+                    .filter_map(|i| self.poly_at(i))
+                    .map(|poly| poly.map_points(&mut *f))
+                    .collect();
+                let len = polys.len().try_into().expect("len must be a valid i32");
+                let polys: Vec<*const tg_poly> = polys.iter().map(|poly| poly.as_raw() as *const _).collect();
+                let polys = polys.as_ptr();
+                match dims {
+                    (false, false) => unsafe { GeometryConstructors::tg_geom_new_multipolygon(polys, len) },
+                    (true, false) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipolygon_z(polys, len, coords, ncoords)
+                    },
+                    (false, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipolygon_m(polys, len, coords, ncoords)
+                    },
+                    (true, true) => unsafe {
+                        GeometryConstructorsEx::tg_geom_new_multipolygon_zm(polys, len, coords, ncoords)
+                    },
+                }
+            }
+            GeomType::GeometryCollection => {
+                let geoms: Vec<Geom> = (0..self.num_geometries())
+                    .filter_map(|i| self.geometry_at(i))
+                    .map(|geom| geom.map_points_dyn(&mut *f))
+                    .collect();
+                return Geom::new_geometrycollection(&geoms);
+            }
+        };
+        raw.into()
+    }
+}
+
+#[cfg(feature = "atomics")]
+unsafe impl Send for Geom {}
+#[cfg(feature = "atomics")]
+unsafe impl Sync for Geom {}
+
 impl Clone for Geom {
     fn clone(&self) -> Self {
         let raw = unsafe { GeometryConstructors::tg_geom_clone(self.as_raw()) };
@@ -89,9 +517,44 @@ impl Drop for Geom {
 impl From<*mut tg_geom> for Geom {
     #[warn(clippy::not_unsafe_ptr_arg_deref)]
     fn from(value: *mut tg_geom) -> Self {
-        if value.is_null() {
-            Geom::handle_alloc_error()
-        }
-        unsafe { Geom::from_raw_unchecked(value) } // false clippy alarm?
+        Geom::from_raw(value).unwrap_or_else(|| Geom::handle_alloc_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geom, Point, Poly, Ring};
+
+    #[test]
+    fn equality() {
+        let square = Ring::new(&[
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(1., 1.),
+            Point::new(0., 1.),
+            Point::new(0., 0.),
+        ]);
+        let rotated = Ring::new(&[
+            Point::new(1., 1.),
+            Point::new(0., 1.),
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(1., 1.),
+        ]);
+        let a = Poly::from(square.clone()).geom();
+        let b = Poly::from(rotated).geom();
+        assert!(a.eq_exact(&a.duplicate()));
+        assert!(!a.eq_exact(&b));
+        assert!(a.eq_topo(&b));
+        assert!(!a.eq_topo(&Point::new(0., 0.).geom()));
+
+        let multi = Geom::new_multipolygon(&[Poly::from(square)]);
+        assert!(!a.eq_exact(&multi));
+        assert!(a.eq_topo(&multi));
+
+        let collection = Geom::new_geometrycollection(&[a.clone(), Point::new(2., 2.).geom()]);
+        let other = Geom::new_geometrycollection(&[a, Point::new(2., 3.).geom()]);
+        assert!(collection.eq_exact(&collection.duplicate()));
+        assert!(!collection.eq_exact(&other));
     }
 }
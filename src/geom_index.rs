@@ -0,0 +1,358 @@
+//! A spatial index over many geometries.
+//!
+//! tg indexes the segments within a single line or ring, but not collections
+//! of geometries. [`GeomIndex`] keeps the rects of its geometries in a packed
+//! Hilbert R-tree, in the style of [flatbush]: the rects are sorted along a
+//! Hilbert curve through their centers, then grouped into nodes of
+//! [`NODE_SIZE`], level by level up to a single root. The tree is built once
+//! and not changed after. Queries walk the tree for candidate rects, then
+//! check the geometries themselves with tg's predicates.
+//!
+//! [flatbush]: https://github.com/mourner/flatbush
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{Geom, Point, Rect};
+
+/// The most children of a node in the tree.
+const NODE_SIZE: usize = 16;
+
+/// Geometries with payloads, indexed by their rects.
+#[derive(Clone)]
+pub struct GeomIndex<T> {
+    items: Vec<(Geom, T)>,
+    /// The rects of the nodes, level by level from the leaves, which are the
+    /// rects of the items in Hilbert order.
+    rects: Vec<Rect>,
+    /// For each leaf the index of its item, and for each other node the
+    /// position of its first child in `rects`.
+    indices: Vec<usize>,
+    /// The end of each level in `rects`.
+    level_ends: Vec<usize>,
+}
+
+impl<T> GeomIndex<T> {
+    /// Index the geometries, which keep their order for [`GeomIndex::get`].
+    pub fn new(items: Vec<(Geom, T)>) -> GeomIndex<T> {
+        let n = items.len();
+        let item_rects: Vec<Rect> = items.iter().map(|(geom, _)| geom.rect()).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        if n > NODE_SIZE {
+            let bounds = item_rects.iter().copied().reduce(Rect::expand).expect("there are items");
+            let (min, max) = (bounds.min(), bounds.max());
+            let scale = |v: f64, lo: f64, hi: f64| {
+                if hi > lo {
+                    (((v - lo) / (hi - lo)) * HILBERT_MAX as f64) as u32
+                } else {
+                    0
+                }
+            };
+            let keys: Vec<u64> = item_rects
+                .iter()
+                .map(|rect| {
+                    let c = rect.center();
+                    hilbert(scale(c.x, min.x, max.x), scale(c.y, min.y, max.y))
+                })
+                .collect();
+            order.sort_by_key(|&i| keys[i]);
+        }
+        let mut rects: Vec<Rect> = order.iter().map(|&i| item_rects[i]).collect();
+        let mut indices = order;
+        let mut level_ends = vec![n];
+        let mut start = 0;
+        while rects.len() - start > 1 {
+            let end = rects.len();
+            for first in (start..end).step_by(NODE_SIZE) {
+                let last = (first + NODE_SIZE).min(end);
+                let rect = rects[first..last].iter().copied().reduce(Rect::expand).expect("nodes are not empty");
+                rects.push(rect);
+                indices.push(first);
+            }
+            start = end;
+            level_ends.push(rects.len());
+        }
+        GeomIndex {
+            items,
+            rects,
+            indices,
+            level_ends,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// The geometry and payload at `index`, in the order they were given.
+    pub fn get(&self, index: usize) -> Option<(&Geom, &T)> {
+        self.items.get(index).map(|(geom, value)| (geom, value))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Geom, &T)> + '_ {
+        self.items.iter().map(|(geom, value)| (geom, value))
+    }
+
+    /// The indices of the items whose rects intersect `rect`, in no
+    /// particular order.
+    ///
+    /// This only compares rects, so the geometries themselves may not
+    /// intersect `rect`.
+    pub fn search_rect(&self, rect: Rect) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = self.root().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if !self.rects[node].intersects_rect(rect) {
+                continue;
+            }
+            if self.is_leaf(node) {
+                found.push(self.indices[node]);
+            } else {
+                stack.extend(self.children(node));
+            }
+        }
+        found
+    }
+
+    /// The items whose geometries intersect `geom`.
+    pub fn query_intersects<'a>(&'a self, geom: &'a Geom) -> impl Iterator<Item = (&'a Geom, &'a T)> + 'a {
+        self.refine(self.search_rect(geom.rect()), move |item| item.intersects(geom))
+    }
+
+    /// The items whose geometries contain `point`, as [`Geom::contains`]
+    /// decides, so points on a polygon's boundary are not contained.
+    pub fn query_contains_point(&self, point: Point) -> impl Iterator<Item = (&Geom, &T)> + '_ {
+        let point_geom = point.geom();
+        self.refine(self.search_rect(Rect::new(point, point)), move |item| {
+            item.contains(&point_geom)
+        })
+    }
+
+    /// Up to `k` items nearest to `point`, with their distances, nearest
+    /// first.
+    ///
+    /// Distances are to the geometries, not their rects, so a point inside a
+    /// polygon is at distance zero. Empty geometries are never returned.
+    pub fn nearest(&self, point: Point, k: usize) -> Vec<(&Geom, &T, f64)> {
+        let point_geom = point.geom();
+        let mut found = Vec::new();
+        let mut queue = BinaryHeap::new();
+        if let Some(root) = self.root() {
+            queue.push(Candidate {
+                distance: self.rects[root].distance_to_point(point),
+                node: root,
+                item: false,
+            });
+        }
+        // Rects are never further away than what they hold, so once an item
+        // comes off the queue, nothing left can be nearer.
+        while let Some(Candidate { distance, node, item }) = queue.pop() {
+            if found.len() == k {
+                break;
+            }
+            if item {
+                let (geom, value) = &self.items[node];
+                found.push((geom, value, distance));
+            } else if self.is_leaf(node) {
+                let index = self.indices[node];
+                let distance = self.items[index].0.distance(&point_geom);
+                if distance.is_finite() {
+                    queue.push(Candidate {
+                        distance,
+                        node: index,
+                        item: true,
+                    });
+                }
+            } else {
+                for child in self.children(node) {
+                    queue.push(Candidate {
+                        distance: self.rects[child].distance_to_point(point),
+                        node: child,
+                        item: false,
+                    });
+                }
+            }
+        }
+        found
+    }
+
+    fn refine<'a>(
+        &'a self,
+        candidates: Vec<usize>,
+        predicate: impl Fn(&Geom) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a Geom, &'a T)> + 'a {
+        let mut candidates = candidates;
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .map(|i| &self.items[i])
+            .filter(move |(geom, _)| predicate(geom))
+            .map(|(geom, value)| (geom, value))
+    }
+
+    fn root(&self) -> Option<usize> {
+        self.rects.len().checked_sub(1)
+    }
+
+    fn is_leaf(&self, node: usize) -> bool {
+        node < self.items.len()
+    }
+
+    /// The positions of a node's children, which run to the end of their
+    /// level at most.
+    fn children(&self, node: usize) -> std::ops::Range<usize> {
+        let first = self.indices[node];
+        let level_end = self
+            .level_ends
+            .iter()
+            .copied()
+            .find(|&end| end > first)
+            .expect("children are within a level");
+        first..(first + NODE_SIZE).min(level_end)
+    }
+}
+
+impl<T> FromIterator<(Geom, T)> for GeomIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (Geom, T)>>(iter: I) -> Self {
+        GeomIndex::new(iter.into_iter().collect())
+    }
+}
+
+/// A node or item waiting in the queue of a nearest neighbor search, which
+/// pops the smallest distance first.
+struct Candidate {
+    distance: f64,
+    node: usize,
+    /// Whether `node` is the index of an item, at its exact distance.
+    item: bool,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed for a min-heap. At equal distances items come first, so
+        // they are returned before nodes which can't hold anything nearer.
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then(self.item.cmp(&other.item))
+            .then(other.node.cmp(&self.node))
+    }
+}
+
+/// The side of the grid the Hilbert curve runs through, less one.
+const HILBERT_MAX: u32 = (1 << 16) - 1;
+
+/// The distance along a Hilbert curve through a `2^16 × 2^16` grid of the
+/// cell at `x`, `y`.
+fn hilbert(x: u32, y: u32) -> u64 {
+    let (mut x, mut y) = (u64::from(x), u64::from(y));
+    let n = u64::from(HILBERT_MAX) + 1;
+    let mut d = 0;
+    let mut s = n / 2;
+    while s > 0 {
+        let rx = u64::from(x & s > 0);
+        let ry = u64::from(y & s > 0);
+        d += s * s * ((3 * rx) ^ ry);
+        // rotate the quadrant so the curve inside it runs the right way
+        if ry == 0 {
+            if rx == 1 {
+                x = n - 1 - x;
+                y = n - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeomIndex;
+    use crate::test_util::square;
+    use crate::{Geom, Line, Point, Rect};
+
+    /// Squares of varying size on a 30 by 30 grid, with a diagonal line.
+    fn index() -> GeomIndex<usize> {
+        let mut geoms: Vec<Geom> = (0..900)
+            .map(|i| square((i % 30) as f64 * 3., (i / 30) as f64 * 3., 1. + (i % 7) as f64 * 0.4).geom())
+            .collect();
+        geoms.push(Line::new(&[Point::new(0., 0.), Point::new(90., 90.)]).geom());
+        geoms.into_iter().enumerate().map(|(i, geom)| (geom, i)).collect()
+    }
+
+    fn ids<'a>(found: impl Iterator<Item = (&'a Geom, &'a usize)>) -> Vec<usize> {
+        found.map(|(_, &i)| i).collect()
+    }
+
+    #[test]
+    fn queries_match_scans() {
+        let index = index();
+        assert_eq!(index.len(), 901);
+        let query = square(10.5, 20.5, 7.).geom();
+        let expected: Vec<usize> = (0..index.len())
+            .filter(|&i| index.get(i).unwrap().0.intersects(&query))
+            .collect();
+        assert!(expected.len() > 5);
+        assert_eq!(ids(index.query_intersects(&query)), expected);
+
+        let mut candidates = index.search_rect(Rect::new(Point::new(-5., -5.), Point::new(100., 100.)));
+        candidates.sort_unstable();
+        assert_eq!(candidates, (0..901).collect::<Vec<_>>());
+
+        let point = Point::new(31.5, 31.5);
+        let expected: Vec<usize> = (0..index.len())
+            .filter(|&i| index.get(i).unwrap().0.contains(&point.geom()))
+            .collect();
+        assert_eq!(ids(index.query_contains_point(point)), expected);
+        assert!(ids(index.query_contains_point(Point::new(200., 0.))).is_empty());
+    }
+
+    #[test]
+    fn nearest() {
+        let index = index();
+        let point = Point::new(45.2, -3.);
+        let mut expected: Vec<(usize, f64)> = (0..index.len())
+            .map(|i| (i, index.get(i).unwrap().0.distance(&point.geom())))
+            .collect();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let found: Vec<(usize, f64)> = index.nearest(point, 5).into_iter().map(|(_, &i, d)| (i, d)).collect();
+        assert_eq!(found.len(), 5);
+        for (found, expected) in found.iter().zip(&expected) {
+            assert_eq!(found.1, expected.1);
+        }
+        // Inside a square, which is nearer than its rect's neighbors.
+        let (_, &i, distance) = index.nearest(Point::new(0.5, 0.5), 1)[0];
+        assert_eq!((i, distance), (0, 0.));
+        assert_eq!(index.nearest(point, 2000).len(), 901);
+    }
+
+    #[test]
+    fn small_and_empty() {
+        let empty: GeomIndex<()> = GeomIndex::new(Vec::new());
+        assert!(empty.is_empty());
+        assert!(empty.search_rect(Rect::new(Point::new(0., 0.), Point::new(1., 1.))).is_empty());
+        assert!(empty.nearest(Point::new(0., 0.), 3).is_empty());
+
+        let one = GeomIndex::new(vec![(square(0., 0., 1.).geom(), "a")]);
+        assert_eq!(one.query_contains_point(Point::new(0.5, 0.5)).count(), 1);
+        assert_eq!(one.nearest(Point::new(3., 0.5), 1)[0].2, 2.);
+    }
+}
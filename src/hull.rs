@@ -0,0 +1,184 @@
+//! Convex hulls.
+
+use crate::{point::cross, Geom, GeomType, Line, Point, Poly, Ring};
+
+/// Convex hull construction
+impl Ring {
+    /// The smallest convex ring containing all of `points`.
+    ///
+    /// The ring is closed, counter-clockwise and has no collinear vertices.
+    /// Non-finite points are ignored. When the points are all collinear, the
+    /// result is a degenerate ring with zero area running through the extreme
+    /// points.
+    ///
+    /// Uses Andrew's monotone chain algorithm, which is O(n log n).
+    pub fn convex_hull_of_points(points: &[Point]) -> Ring {
+        Ring::new(&hull_points(points.to_vec()))
+    }
+}
+
+/// Convex hull
+impl Ring {
+    /// The smallest convex ring containing every point of the ring.
+    ///
+    /// See [`Ring::convex_hull_of_points`] for the shape of the result.
+    pub fn convex_hull(&self) -> Ring {
+        Ring::convex_hull_of_points(self.points())
+    }
+}
+
+/// Convex hull
+impl Line {
+    /// The smallest convex ring containing every point of the line.
+    ///
+    /// See [`Ring::convex_hull_of_points`] for the shape of the result.
+    pub fn convex_hull(&self) -> Ring {
+        Ring::convex_hull_of_points(self.points())
+    }
+}
+
+/// Convex hull
+impl Poly {
+    /// The smallest convex ring containing the polygon.
+    ///
+    /// Holes never contribute to the hull, so this is the hull of the
+    /// exterior.
+    pub fn convex_hull(&self) -> Ring {
+        self.exterior().convex_hull()
+    }
+}
+
+/// Convex hull
+impl Geom {
+    /// The smallest convex ring containing every point of the geometry.
+    ///
+    /// See [`Ring::convex_hull_of_points`] for the shape of the result.
+    pub fn convex_hull(&self) -> Ring {
+        let mut points = Vec::new();
+        extend_points(self, &mut points);
+        Ring::new(&hull_points(points))
+    }
+}
+
+/// Collect the points which can be on the hull of `geom`.
+fn extend_points(geom: &Geom, points: &mut Vec<Point>) {
+    if geom.is_empty() {
+        return;
+    }
+    match geom.geom_type() {
+        GeomType::Point => points.push(geom.point()),
+        GeomType::LineString => {
+            if let Some(line) = geom.line() {
+                points.extend_from_slice(line.points());
+            }
+        }
+        GeomType::Polygon => {
+            if let Some(poly) = geom.poly() {
+                points.extend_from_slice(poly.exterior().points());
+            }
+        }
+        GeomType::MultiPoint => points.extend((0..geom.num_points()).filter_map(|i| geom.point_at(i))),
+        GeomType::MultiLineString => {
+            for line in (0..geom.num_lines()).filter_map(|i| geom.line_at(i)) {
+                points.extend_from_slice(line.points());
+            }
+        }
+        GeomType::MultiPolygon => {
+            for poly in (0..geom.num_polys()).filter_map(|i| geom.poly_at(i)) {
+                points.extend_from_slice(poly.exterior().points());
+            }
+        }
+        GeomType::GeometryCollection => {
+            for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                extend_points(&child, points);
+            }
+        }
+    }
+}
+
+/// The closed, counter-clockwise hull of `points`.
+fn hull_points(mut points: Vec<Point>) -> Vec<Point> {
+    points.retain(|p| p.x.is_finite() && p.y.is_finite());
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        if let Some(&first) = points.first() {
+            points.push(first);
+        }
+        return points;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() + 1);
+    // lower chain, left to right
+    for &p in &points {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // upper chain, right to left
+    let lower_len = hull.len() + 1;
+    for &p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0. {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // The upper chain ends back at the first point, which closes the ring.
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geom, Line, Point, Ring};
+
+    #[test]
+    fn hull_of_points() {
+        let points = [
+            Point::new(0., 0.),
+            Point::new(2., 0.),
+            Point::new(1., 1.),
+            Point::new(1., 0.),
+            Point::new(2., 2.),
+            Point::new(0., 2.),
+            Point::new(0.5, 1.5),
+        ];
+        let hull = Ring::convex_hull_of_points(&points);
+        assert_eq!(
+            hull.points(),
+            &[
+                Point::new(0., 0.),
+                Point::new(2., 0.),
+                Point::new(2., 2.),
+                Point::new(0., 2.),
+                Point::new(0., 0.),
+            ]
+        );
+        assert!(!hull.clockwise());
+        assert!(hull.convex());
+        assert_eq!(hull.area(), 4.);
+        assert_eq!(hull.convex_hull(), hull);
+    }
+
+    #[test]
+    fn collinear_points() {
+        let line = Line::new(&[Point::new(0., 0.), Point::new(1., 1.), Point::new(2., 2.)]);
+        let hull = line.convex_hull();
+        assert_eq!(hull.area(), 0.);
+        assert_eq!(hull.point(0), Some(Point::new(0., 0.)));
+        assert!(hull.points().contains(&Point::new(2., 2.)));
+        assert!(!hull.points().contains(&Point::new(1., 1.)));
+        assert_eq!(Ring::convex_hull_of_points(&[]).num_points(), 0);
+    }
+
+    #[test]
+    fn hull_of_geometry() {
+        let geom = Geom::new_geometrycollection(&[
+            Point::new(5., 5.).geom(),
+            Line::new(&[Point::new(0., 0.), Point::new(4., 0.)]).geom(),
+        ]);
+        let hull = geom.convex_hull();
+        assert_eq!(hull.num_segments(), 3);
+        assert_eq!(hull.area(), 10.);
+    }
+}
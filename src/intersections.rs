@@ -0,0 +1,189 @@
+//! Where lines cross themselves and each other.
+//!
+//! Candidate segment pairs come from the segment index through
+//! [`Line::line_search`], and the intersection points are then computed
+//! exactly for each pair. Collinear segments which overlap are reported once,
+//! at the start of the overlap.
+
+use crate::{linear_referencing::cumulative_lengths, segment::SegmentChain, Line, Point, Segment};
+
+/// A point where two lines intersect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crossing {
+    pub point: Point,
+    /// The index of the segment of the first line which contains the point.
+    pub segment: usize,
+    /// The index of the segment of the other line which contains the point.
+    pub other_segment: usize,
+    /// How far along the first line the point is, as a fraction of its
+    /// length.
+    pub fraction: f64,
+    /// How far along the other line the point is, as a fraction of its
+    /// length.
+    pub other_fraction: f64,
+}
+
+/// Intersections
+impl Line {
+    /// Whether the line never touches itself, other than where a closed line
+    /// ends at its first point.
+    pub fn is_simple(&self) -> bool {
+        self_intersections(self, true).is_empty()
+    }
+
+    /// The points where the line touches or crosses itself, in order of the
+    /// segments involved.
+    ///
+    /// Consecutive segments always share a vertex, which is not counted
+    /// unless the line doubles back over itself there. The same goes for the
+    /// first and last points of a closed line.
+    pub fn self_intersections(&self) -> Vec<Point> {
+        let mut points: Vec<Point> = Vec::new();
+        for (_, _, point) in self_intersections(self, false) {
+            if !points.contains(&point) {
+                points.push(point);
+            }
+        }
+        points
+    }
+
+    /// The points where the two lines intersect, ordered by how far along
+    /// `self` they are.
+    ///
+    /// A crossing at a vertex is only reported once, even though it lies on
+    /// two segments.
+    pub fn crossings(&self, other: &Line) -> Vec<Crossing> {
+        let lengths = cumulative_lengths(self.points());
+        let other_lengths = cumulative_lengths(other.points());
+        let fraction = |lengths: &[f64], index: usize, segment: Segment, point: Point| {
+            let total = lengths.last().copied().unwrap_or(0.);
+            if total == 0. {
+                0.
+            } else {
+                (lengths[index] + segment.a().distance(point)) / total
+            }
+        };
+        let mut crossings = Vec::new();
+        self.line_search(other, &mut |a: Segment, i: usize, b: Segment, j: usize| {
+            if let Some(point) = a.intersection(b) {
+                crossings.push(Crossing {
+                    point,
+                    segment: i,
+                    other_segment: j,
+                    fraction: fraction(&lengths, i, a, point),
+                    other_fraction: fraction(&other_lengths, j, b, point),
+                });
+            }
+            true
+        });
+        crossings.sort_by(|x, y| {
+            x.fraction
+                .total_cmp(&y.fraction)
+                .then(x.other_fraction.total_cmp(&y.other_fraction))
+                .then(x.segment.cmp(&y.segment))
+                .then(x.other_segment.cmp(&y.other_segment))
+        });
+        crossings.dedup_by(|x, y| {
+            x.point == y.point && x.fraction == y.fraction && x.other_fraction == y.other_fraction
+        });
+        crossings
+    }
+}
+
+/// The pairs of segments `(i, j)` with `i < j` where the line intersects
+/// itself, and where, sorted. Stops after the first if `first_only`.
+fn self_intersections(line: &Line, first_only: bool) -> Vec<(usize, usize, Point)> {
+    let nsegs = line.num_segments();
+    let closed = nsegs > 1 && line.points().first() == line.points().last();
+    let chain = SegmentChain::new((0..nsegs).filter_map(|i| line.segment(i)), closed);
+    let mut found = Vec::new();
+    line.line_search(line, &mut |a: Segment, i: usize, b: Segment, j: usize| {
+        if i >= j {
+            // every pair is visited in both orders, and each segment with itself
+            return true;
+        }
+        if let Some(point) = chain.touch(a, i, b, j) {
+            found.push((i, j, point));
+        }
+        !first_only || found.is_empty()
+    });
+    found.sort_by_key(|&(i, j, _)| (i, j));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Crossing;
+    use crate::{Line, Point};
+
+    fn line(points: &[(f64, f64)]) -> Line {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Line::new(&points)
+    }
+
+    #[test]
+    fn simple_lines() {
+        assert!(line(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.)]).is_simple());
+        // closed
+        assert!(line(&[(0., 0.), (1., 0.), (1., 1.), (0., 0.)]).is_simple());
+        assert!(Line::new(&[]).is_simple());
+
+        let loop_ = line(&[(0., 0.), (2., 0.), (2., 2.), (1., 2.), (1., -1.)]);
+        assert!(!loop_.is_simple());
+        assert_eq!(loop_.self_intersections(), vec![Point::new(1., 0.)]);
+
+        // touches an earlier vertex
+        let touch = line(&[(0., 0.), (2., 0.), (2., 2.), (1., 2.), (2., 0.), (3., 0.)]);
+        assert_eq!(touch.self_intersections(), vec![Point::new(2., 0.)]);
+
+        // doubles back along itself
+        let spike = line(&[(0., 0.), (2., 0.), (1., 0.)]);
+        assert_eq!(spike.self_intersections(), vec![Point::new(1., 0.)]);
+
+        // repeated points
+        let repeated = line(&[(0., 0.), (4., 0.), (4., 0.), (4., 4.)]);
+        assert!(repeated.is_simple());
+        assert!(repeated.self_intersections().is_empty());
+        assert!(line(&[(0., 0.), (1., 0.), (1., 1.), (0., 0.), (0., 0.)]).is_simple());
+        let spike = line(&[(0., 0.), (2., 0.), (2., 0.), (1., 0.)]);
+        assert_eq!(spike.self_intersections(), vec![Point::new(1., 0.)]);
+    }
+
+    #[test]
+    fn crossings() {
+        let route = line(&[(0., 0.), (10., 0.), (10., 10.)]);
+        let road = line(&[(5., -5.), (5., 5.), (15., 5.)]);
+        let crossings = route.crossings(&road);
+        assert_eq!(
+            crossings,
+            vec![
+                Crossing {
+                    point: Point::new(5., 0.),
+                    segment: 0,
+                    other_segment: 0,
+                    fraction: 0.25,
+                    other_fraction: 0.25,
+                },
+                Crossing {
+                    point: Point::new(10., 5.),
+                    segment: 1,
+                    other_segment: 1,
+                    fraction: 0.75,
+                    other_fraction: 0.75,
+                },
+            ]
+        );
+        let back = road.crossings(&route);
+        assert_eq!(back[0].fraction, 0.25);
+        assert_eq!(back[0].other_segment, 0);
+
+        // through a vertex of the route
+        let diagonal = line(&[(5., -5.), (15., 5.)]);
+        let crossings = route.crossings(&diagonal);
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].point, Point::new(10., 0.));
+        assert_eq!(crossings[0].fraction, 0.5);
+
+        assert!(route.crossings(&line(&[(20., 20.), (30., 30.)])).is_empty());
+    }
+}
@@ -1,20 +1,58 @@
+pub use buffer::{BufferOptions, CapStyle, JoinStyle};
+pub use earcut::Triangulation;
+pub use error::ConstructionError;
 pub use geom::Geom;
+pub use geom_index::GeomIndex;
+pub use intersections::Crossing;
 pub use line::Line;
+pub use line_merge::{line_merge, line_merge_with_tolerance};
 pub use point::Point;
+pub use poly::Poly;
+pub use polygonize::{assemble_polys, polygonize};
 pub use rect::Rect;
+pub use relate::{IntersectionMatrix, Location, PatternError};
 pub use ring::Ring;
 pub use segment::Segment;
+pub use spatial_join::{spatial_join, Predicate};
+pub use validate::{Component, ValidationError, ValidationErrorKind};
 pub use visitors::{SearchVisitor,NearestSegmentVisitor};
+pub use winding::Winding;
+mod buffer;
+mod centroid;
+mod clip;
+mod distance;
+mod earcut;
+mod error;
+pub mod geohash;
 mod geom;
+mod geom_index;
+mod hull;
+mod intersections;
 mod line;
+mod line_merge;
+mod linear_referencing;
+pub mod mvt;
+mod overlay;
 mod point;
 mod poly;
+mod polygonize;
+pub mod projection;
 mod rect;
+mod relate;
 mod ring;
 mod segment;
+mod similarity;
+mod simplify;
+mod spatial_join;
+#[cfg(test)]
+mod test_util;
+pub mod tiles;
+pub mod triangulation;
+mod validate;
 mod visitors;
+mod winding;
 
-use tg_sys::tg_index;
+use tg_sys::{tg_geom_type, tg_index};
 
 #[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
 pub enum IndexType {
@@ -46,3 +84,42 @@ impl From<tg_index> for IndexType {
         }
     }
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GeomType {
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection,
+}
+
+impl From<GeomType> for tg_geom_type {
+    fn from(value: GeomType) -> Self {
+        match value {
+            GeomType::Point => tg_geom_type::TG_POINT,
+            GeomType::LineString => tg_geom_type::TG_LINESTRING,
+            GeomType::Polygon => tg_geom_type::TG_POLYGON,
+            GeomType::MultiPoint => tg_geom_type::TG_MULTIPOINT,
+            GeomType::MultiLineString => tg_geom_type::TG_MULTILINESTRING,
+            GeomType::MultiPolygon => tg_geom_type::TG_MULTIPOLYGON,
+            GeomType::GeometryCollection => tg_geom_type::TG_GEOMETRYCOLLECTION,
+        }
+    }
+}
+
+impl From<tg_geom_type> for GeomType {
+    fn from(value: tg_geom_type) -> Self {
+        match value {
+            tg_geom_type::TG_POINT => Self::Point,
+            tg_geom_type::TG_LINESTRING => Self::LineString,
+            tg_geom_type::TG_POLYGON => Self::Polygon,
+            tg_geom_type::TG_MULTIPOINT => Self::MultiPoint,
+            tg_geom_type::TG_MULTILINESTRING => Self::MultiLineString,
+            tg_geom_type::TG_MULTIPOLYGON => Self::MultiPolygon,
+            tg_geom_type::TG_GEOMETRYCOLLECTION => Self::GeometryCollection,
+        }
+    }
+}
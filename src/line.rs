@@ -19,7 +19,10 @@ use std::{
     ptr::NonNull,
 };
 use tg_sys::{tg_line, tg_point, tg_rect, tg_segment, GeometryConstructors, LineFuncs};
-use crate::{Geom, IndexType, NearestSegmentVisitor, Point, Rect, SearchVisitor, Segment};
+use crate::{
+    error::check_points, visitors::NearestToPoint, ConstructionError, Geom, IndexType,
+    NearestSegmentVisitor, Point, Rect, SearchVisitor, Segment,
+};
 
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserializer, Deserialize};
@@ -50,6 +53,31 @@ impl Line {
         unsafe { Line::from_raw_unchecked(raw) }
     }
 
+    /// Create a line, rejecting input which [`Line::new`] would accept but
+    /// which does not make a usable line.
+    ///
+    /// The line needs at least 2 points, finite coordinates, and no more than
+    /// `i32::MAX` points.
+    pub fn try_from_points(points: &[Point]) -> Result<Line, ConstructionError> {
+        Line::try_from_points_indexed(points, IndexType::Default)
+    }
+
+    pub fn try_from_points_indexed(points: &[Point], index: IndexType) -> Result<Line, ConstructionError> {
+        let len = check_points(points)?;
+        if points.len() < 2 {
+            return Err(ConstructionError::TooFewPoints {
+                count: points.len(),
+                min: 2,
+            });
+        }
+        let ptr = points.as_ptr() as *const tg_point;
+        let raw = unsafe { LineFuncs::tg_line_new_ix(ptr, len, index.into()) };
+        if raw.is_null() {
+            Self::handle_alloc_error();
+        }
+        Ok(unsafe { Line::from_raw_unchecked(raw) })
+    }
+
     pub fn as_raw(&self) -> *mut tg_line {
         self.inner.as_ptr()
     }
@@ -77,6 +105,16 @@ impl Line {
         unsafe { Line::from_raw_unchecked(raw) }
     }
 
+    /// Take a new reference to a line owned by another tg object, such as a
+    /// LineString geometry.
+    pub(crate) fn clone_from_raw(raw: *const tg_line) -> Line {
+        let new = unsafe { LineFuncs::tg_line_clone(raw) };
+        if new.is_null() {
+            Line::handle_alloc_error();
+        }
+        unsafe { Line::from_raw_unchecked(new) }
+    }
+
     pub fn geom(&self) -> Geom {
         let raw = unsafe { GeometryConstructors::tg_geom_new_linestring(self.as_raw()) };
         if raw.is_null() {
@@ -277,6 +315,14 @@ impl Line {
         }
     }
 
+    /// The segment closest to `point`, with its index and distance, found
+    /// through the segment index.
+    pub(crate) fn nearest_segment_to_point(&self, point: Point) -> Option<(Segment, usize, f64)> {
+        let mut visitor = NearestToPoint::new(point);
+        self.nearest_segment(&mut visitor);
+        visitor.nearest
+    }
+
     #[allow(unused_variables, unused_mut, clippy::let_and_return)]
     pub fn simple_nearest_segment(&self, point: Point) -> Vec<Segment> {
         let mut vec = Vec::with_capacity(self.num_segments());
@@ -293,6 +339,20 @@ impl Line {
     }
 }
 
+/// Coordinate mapping
+impl Line {
+    /// Create a new line with every point passed through `f`.
+    pub fn map_points<F: FnMut(Point) -> Point>(&self, f: F) -> Line {
+        self.map_points_indexed(f, IndexType::Default)
+    }
+
+    /// Like [`Line::map_points`], but with the given index.
+    pub fn map_points_indexed<F: FnMut(Point) -> Point>(&self, f: F, index: IndexType) -> Line {
+        let points: Vec<Point> = self.points().iter().copied().map(f).collect();
+        Line::new_indexed(&points, index)
+    }
+}
+
 #[cfg(feature = "atomics")]
 unsafe impl Send for Line {}
 #[cfg(feature = "atomics")]
@@ -353,7 +413,7 @@ mod tests {
     use std::convert::identity;
 
     use super::Line;
-    use crate::{Point, Rect, SearchVisitor, Segment};
+    use crate::{ConstructionError, IndexType, Point, Rect, SearchVisitor, Segment};
 
     #[test]
     fn line_search() {
@@ -439,6 +499,20 @@ mod tests {
         assert_eq!(visitor.ct, 4);
     }
 
+    #[test]
+    fn checked_construction() {
+        let line = Line::try_from_points(&[Point::new(0., 0.), Point::new(1., 1.)]).unwrap();
+        assert_eq!(line.num_points(), 2);
+        assert!(matches!(
+            Line::try_from_points(&[Point::new(0., 0.)]),
+            Err(ConstructionError::TooFewPoints { count: 1, min: 2 })
+        ));
+        assert!(matches!(
+            Line::try_from_points(&[Point::new(0., 0.), Point::new(1., f64::INFINITY)]),
+            Err(ConstructionError::NonFiniteCoordinate { index: 1, .. })
+        ));
+    }
+
     #[test]
     #[allow(clippy::get_first)]
     fn points() {
@@ -482,6 +556,17 @@ mod tests {
                 true
             },
         ));
-        assert_eq!(ct, 0);
+        assert_eq!(ct, 3);
+    }
+
+    #[test]
+    fn map_points_indexed() {
+        let points: Vec<Point> = (0..100).map(|i| Point::new(i as f64, (i % 7) as f64)).collect();
+        let shift = |p: Point| Point::new(p.x, p.y + 1.);
+        let line = Line::new_indexed(&points, IndexType::None);
+        assert!(line.map_points(shift).index_num_levels() > 0);
+        let shifted = line.map_points_indexed(shift, IndexType::None);
+        assert_eq!(shifted.index_num_levels(), 0);
+        assert_eq!(shifted.point(1), Some(Point::new(1., 2.)));
     }
 }
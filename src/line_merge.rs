@@ -0,0 +1,247 @@
+//! Joining lines end to end into longer lines.
+//!
+//! Lines are joined where exactly two line ends meet. Where three or more
+//! meet, the network branches and the lines end there, so the merged lines
+//! follow the same paths as the originals.
+
+use std::collections::HashMap;
+
+use crate::{Line, Point};
+
+/// Merging
+impl Line {
+    /// The line followed by `other`.
+    ///
+    /// When `other` starts where `self` ends, the shared point appears once.
+    /// Otherwise the gap between them becomes a segment of the new line.
+    pub fn concat(&self, other: &Line) -> Line {
+        let mut points = self.points().to_vec();
+        let rest = match (points.last(), other.points().first()) {
+            (Some(last), Some(first)) if last == first => &other.points()[1..],
+            _ => other.points(),
+        };
+        points.extend_from_slice(rest);
+        Line::new(&points)
+    }
+}
+
+/// The lines joined end to end wherever exactly two of their ends meet,
+/// reversing lines where needed.
+///
+/// Lines are only joined at their ends, and stop where three or more ends
+/// meet. Lines which form a loop become a closed line. See
+/// [`line_merge_with_tolerance`] to join ends which are close but not equal.
+pub fn line_merge(lines: &[Line]) -> Vec<Line> {
+    line_merge_with_tolerance(lines, 0.)
+}
+
+/// Like [`line_merge`], but treating line ends within `tolerance` of each
+/// other as meeting.
+///
+/// Where ends are joined, the first line's end is kept and the next line's
+/// start is dropped.
+///
+/// # Panics
+///
+/// If `tolerance` is negative or not finite.
+pub fn line_merge_with_tolerance(lines: &[Line], tolerance: f64) -> Vec<Line> {
+    assert!(
+        tolerance >= 0. && tolerance.is_finite(),
+        "tolerance must be finite and non-negative"
+    );
+    let lines: Vec<&Line> = lines.iter().filter(|line| !line.is_empty()).collect();
+    let mut nodes = Nodes::new(tolerance);
+    // The nodes at the start and end of each line.
+    let ends: Vec<(usize, usize)> = lines
+        .iter()
+        .map(|line| {
+            let points = line.points();
+            (nodes.find(points[0]), nodes.find(points[points.len() - 1]))
+        })
+        .collect();
+    // The line ends at each node, and whether each is the line's start.
+    let mut at_node: Vec<Vec<(usize, bool)>> = vec![Vec::new(); nodes.len()];
+    for (i, &(start, end)) in ends.iter().enumerate() {
+        at_node[start].push((i, true));
+        at_node[end].push((i, false));
+    }
+
+    let mut used = vec![false; lines.len()];
+    let mut merged = Vec::new();
+    let mut walk = |first: usize, forwards: bool, used: &mut Vec<bool>| {
+        let mut points: Vec<Point> = Vec::new();
+        let (mut line, mut forwards) = (first, forwards);
+        loop {
+            used[line] = true;
+            let mut line_points = lines[line].points().to_vec();
+            if !forwards {
+                line_points.reverse();
+            }
+            let skip = usize::from(!points.is_empty());
+            points.extend_from_slice(&line_points[skip..]);
+            let node = if forwards { ends[line].1 } else { ends[line].0 };
+            let [a, b] = match at_node[node][..] {
+                [a, b] => [a, b],
+                _ => break,
+            };
+            // The other line end at the node. A line which loops back to
+            // its own start has both ends here.
+            let (next, is_start) = if a == (line, !forwards) { b } else { a };
+            if used[next] {
+                break;
+            }
+            (line, forwards) = (next, is_start);
+        }
+        merged.push(Line::new(&points));
+    };
+    // Start from the ends which cannot be joined, then walk what is left,
+    // which is loops.
+    for i in 0..lines.len() {
+        if used[i] {
+            continue;
+        }
+        let (start, end) = ends[i];
+        if at_node[start].len() != 2 {
+            walk(i, true, &mut used);
+        } else if at_node[end].len() != 2 {
+            walk(i, false, &mut used);
+        }
+    }
+    for i in 0..lines.len() {
+        if !used[i] {
+            walk(i, true, &mut used);
+        }
+    }
+    merged
+}
+
+/// Line ends grouped into nodes where they are within a tolerance.
+struct Nodes {
+    tolerance: f64,
+    points: Vec<Point>,
+    /// The nodes in each grid cell of the tolerance's size.
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    exact: HashMap<(u64, u64), usize>,
+}
+
+impl Nodes {
+    fn new(tolerance: f64) -> Nodes {
+        Nodes {
+            tolerance,
+            points: Vec::new(),
+            cells: HashMap::new(),
+            exact: HashMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// The node within the tolerance of `point`, adding one if there is none.
+    fn find(&mut self, point: Point) -> usize {
+        let next = self.points.len();
+        if self.tolerance == 0. {
+            // Adding zero turns -0. into 0., which has different bits.
+            let key = ((point.x + 0.).to_bits(), (point.y + 0.).to_bits());
+            let node = *self.exact.entry(key).or_insert(next);
+            if node == next {
+                self.points.push(point);
+            }
+            return node;
+        }
+        let cell = |v: f64| (v / self.tolerance).floor() as i64;
+        let (cx, cy) = (cell(point.x), cell(point.y));
+        let near = (cx - 1..=cx + 1)
+            .flat_map(|x| (cy - 1..=cy + 1).map(move |y| (x, y)))
+            .filter_map(|c| self.cells.get(&c))
+            .flatten()
+            .copied()
+            .filter(|&node| self.points[node].distance(point) <= self.tolerance)
+            .min();
+        if let Some(node) = near {
+            return node;
+        }
+        self.points.push(point);
+        self.cells.entry((cx, cy)).or_default().push(next);
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line_merge, line_merge_with_tolerance};
+    use crate::{Line, Point};
+
+    fn line(points: &[(f64, f64)]) -> Line {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Line::new(&points)
+    }
+
+    fn coords(line: &Line) -> Vec<(f64, f64)> {
+        line.points().iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn concat() {
+        let a = line(&[(0., 0.), (1., 0.)]);
+        let b = line(&[(1., 0.), (1., 1.)]);
+        assert_eq!(coords(&a.concat(&b)), [(0., 0.), (1., 0.), (1., 1.)]);
+        assert_eq!(coords(&b.concat(&a)), [(1., 0.), (1., 1.), (0., 0.), (1., 0.)]);
+    }
+
+    #[test]
+    fn chains_and_branches() {
+        // A path split into pieces pointing both ways, and a fork.
+        let lines = [
+            line(&[(1., 0.), (2., 0.)]),
+            line(&[(0., 0.), (1., 0.)]),
+            line(&[(3., 0.), (2., 0.)]),
+            line(&[(3., 0.), (4., 1.)]),
+            line(&[(3., 0.), (4., -1.)]),
+        ];
+        let merged = line_merge(&lines);
+        let merged: Vec<_> = merged.iter().map(coords).collect();
+        assert_eq!(
+            merged,
+            [
+                vec![(0., 0.), (1., 0.), (2., 0.), (3., 0.)],
+                vec![(3., 0.), (4., 1.)],
+                vec![(3., 0.), (4., -1.)],
+            ]
+        );
+    }
+
+    #[test]
+    fn loops() {
+        let lines = [
+            line(&[(0., 0.), (1., 0.), (1., 1.)]),
+            line(&[(0., 0.), (0., 1.)]),
+            line(&[(1., 1.), (0., 1.)]),
+        ];
+        let merged = line_merge(&lines);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            coords(&merged[0]),
+            [(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)]
+        );
+        assert_eq!(merged[0].length(), 4.);
+    }
+
+    #[test]
+    fn signed_zero() {
+        let lines = [line(&[(1., 1.), (0., 0.)]), line(&[(-0., -0.), (1., -1.)])];
+        let merged = line_merge(&lines);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].num_points(), 3);
+    }
+
+    #[test]
+    fn tolerance() {
+        let lines = [line(&[(0., 0.), (1., 0.)]), line(&[(1.01, 0.), (2., 0.)])];
+        assert_eq!(line_merge(&lines).len(), 2);
+        let merged = line_merge_with_tolerance(&lines, 0.05);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(coords(&merged[0]), [(0., 0.), (1., 0.), (2., 0.)]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Linear referencing: addressing positions along a line by distance or by
+//! fraction of its length.
+//!
+//! Distances and fractions are clamped to the extent of the line, so asking
+//! for a point past the end returns the last point.
+
+use crate::{Line, Point};
+
+/// Linear referencing
+impl Line {
+    /// The point `distance` along the line from its first point.
+    ///
+    /// Returns `None` for an empty line.
+    pub fn point_at_distance(&self, distance: f64) -> Option<Point> {
+        let lengths = cumulative_lengths(self.points());
+        locate(self.points(), &lengths, distance).map(|(_, point)| point)
+    }
+
+    /// The point at `fraction` of the line's length, where `0.` is the first
+    /// point and `1.` the last.
+    ///
+    /// Returns `None` for an empty line.
+    pub fn point_at_fraction(&self, fraction: f64) -> Option<Point> {
+        self.point_at_distance(fraction * self.length())
+    }
+
+    /// The fraction of the line's length at which the point on the line
+    /// closest to `point` lies.
+    ///
+    /// The closest segment is found through the line's index. Lines with zero
+    /// length, including empty lines, locate every point at `0.`.
+    pub fn locate_point(&self, point: Point) -> f64 {
+        let length = self.length();
+        if length == 0. {
+            return 0.;
+        }
+        let Some((segment, index, _)) = self.nearest_segment_to_point(point) else {
+            return 0.;
+        };
+        let lengths = cumulative_lengths(self.points());
+        let along = lengths[index] + segment.a().distance(segment.closest_point(point));
+        (along / length).clamp(0., 1.)
+    }
+
+    /// The part of the line between the fractions `start` and `end` of its
+    /// length.
+    ///
+    /// If `start` is greater than `end`, the substring runs backwards. Empty
+    /// lines have empty substrings.
+    pub fn substring(&self, start: f64, end: f64) -> Line {
+        if start > end {
+            let mut points = self.substring(end, start).points().to_vec();
+            points.reverse();
+            return Line::new(&points);
+        }
+        let points = self.points();
+        let lengths = cumulative_lengths(points);
+        let total = lengths.last().copied().unwrap_or(0.);
+        let (Some((first, a)), Some((last, b))) = (
+            locate(points, &lengths, start * total),
+            locate(points, &lengths, end * total),
+        ) else {
+            return Line::new(&[]);
+        };
+        let mut sub = Vec::with_capacity(last - first + 2);
+        sub.push(a);
+        sub.extend_from_slice(&points[first + 1..=last]);
+        sub.push(b);
+        // Drop the vertices which coincide with the cut points.
+        if sub.len() > 2 && sub[1] == a {
+            sub.remove(1);
+        }
+        if sub.len() > 2 && sub[sub.len() - 2] == b {
+            sub.remove(sub.len() - 2);
+        }
+        Line::new(&sub)
+    }
+
+    /// Split the line in two at `fraction` of its length.
+    pub fn split_at(&self, fraction: f64) -> (Line, Line) {
+        (self.substring(0., fraction), self.substring(fraction, 1.))
+    }
+}
+
+/// The distance along the line to each of its points.
+pub(crate) fn cumulative_lengths(points: &[Point]) -> Vec<f64> {
+    let mut total = 0.;
+    let mut lengths = Vec::with_capacity(points.len());
+    let mut prev = points.first().copied();
+    for &point in points {
+        if let Some(prev) = prev {
+            total += prev.distance(point);
+        }
+        lengths.push(total);
+        prev = Some(point);
+    }
+    lengths
+}
+
+/// The point at `distance` along the line, and the index of the segment it
+/// lies on.
+fn locate(points: &[Point], lengths: &[f64], distance: f64) -> Option<(usize, Point)> {
+    let total = *lengths.last()?;
+    if points.len() == 1 {
+        return Some((0, points[0]));
+    }
+    let distance = distance.clamp(0., total);
+    // The first segment ending at or after `distance`, skipping zero-length
+    // segments.
+    let index = lengths[1..]
+        .partition_point(|&length| length < distance)
+        .min(points.len() - 2);
+    let (a, b) = (points[index], points[index + 1]);
+    let length = lengths[index + 1] - lengths[index];
+    if length == 0. {
+        return Some((index, a));
+    }
+    let t = (distance - lengths[index]) / length;
+    Some((index, Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y))))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Line, Point};
+
+    fn route() -> Line {
+        Line::new(&[
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(20., 10.),
+        ])
+    }
+
+    #[test]
+    fn interpolate() {
+        let line = route();
+        assert_eq!(line.point_at_distance(0.), Some(Point::new(0., 0.)));
+        assert_eq!(line.point_at_distance(15.), Some(Point::new(10., 5.)));
+        assert_eq!(line.point_at_distance(100.), Some(Point::new(20., 10.)));
+        assert_eq!(line.point_at_fraction(0.5), Some(Point::new(10., 5.)));
+        assert_eq!(line.point_at_fraction(-1.), Some(Point::new(0., 0.)));
+        assert_eq!(Line::new(&[]).point_at_fraction(0.5), None);
+    }
+
+    #[test]
+    fn locate() {
+        let line = route();
+        assert_eq!(line.locate_point(Point::new(12., 5.)), 0.5);
+        assert_eq!(line.locate_point(Point::new(-5., -5.)), 0.);
+        assert_eq!(line.locate_point(Point::new(25., 10.)), 1.);
+        assert_eq!(line.locate_point(Point::new(5., 1.)), 5. / 30.);
+    }
+
+    #[test]
+    fn substring_and_split() {
+        let line = route();
+        let sub = line.substring(0.25, 0.75);
+        assert_eq!(
+            sub.points(),
+            &[Point::new(7.5, 0.), Point::new(10., 0.), Point::new(10., 10.), Point::new(12.5, 10.)]
+        );
+        let back = line.substring(0.75, 0.25);
+        assert_eq!(back.point(0), Some(Point::new(12.5, 10.)));
+
+        let (head, tail) = line.split_at(0.5);
+        assert_eq!(head.points(), &[Point::new(0., 0.), Point::new(10., 0.), Point::new(10., 5.)]);
+        assert_eq!(tail.points(), &[Point::new(10., 5.), Point::new(10., 10.), Point::new(20., 10.)]);
+        assert_eq!(head.length() + tail.length(), line.length());
+
+        let (head, tail) = line.split_at(1. / 3.);
+        assert_eq!(head.points(), &[Point::new(0., 0.), Point::new(10., 0.)]);
+        assert_eq!(tail.point(0), Some(Point::new(10., 0.)));
+    }
+}
@@ -0,0 +1,905 @@
+//! Encoding and decoding [Mapbox Vector Tiles] (version 2.1).
+//!
+//! Geometries are in lon/lat degrees, as in [`projection`](crate::projection).
+//! Encoding projects them to Web Mercator, clips them to the tile plus a
+//! buffer, snaps them to the integer grid of the layer's extent, and fixes the
+//! winding order of polygon rings. Geometries that collapse to nothing on the
+//! grid are dropped.
+//!
+//! Decoding reverses the transformation, so a round trip returns the input
+//! snapped to the grid.
+//!
+//! [Mapbox Vector Tiles]: https://github.com/mapbox/vector-tile-spec/tree/master/2.1
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    projection::{lonlat_to_web_mercator, web_mercator_to_lonlat},
+    tiles::Tile,
+    Geom, GeomType, Line, Point, Poly, Rect, Ring,
+};
+
+/// The default extent of a layer, in tile coordinate units.
+pub const DEFAULT_EXTENT: u32 = 4096;
+
+/// A named layer of features.
+#[derive(Clone)]
+pub struct Layer {
+    pub name: String,
+    /// The width and height of the tile in integer tile coordinates.
+    pub extent: u32,
+    pub features: Vec<Feature>,
+}
+
+impl Layer {
+    pub fn new(name: impl Into<String>) -> Layer {
+        Layer {
+            name: name.into(),
+            extent: DEFAULT_EXTENT,
+            features: Vec::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// If `extent` is 0.
+    pub fn with_extent(mut self, extent: u32) -> Layer {
+        assert!(extent > 0, "layer extent must be positive");
+        self.extent = extent;
+        self
+    }
+
+    pub fn with_feature(mut self, feature: Feature) -> Layer {
+        self.features.push(feature);
+        self
+    }
+}
+
+/// A geometry with an optional id and properties.
+#[derive(Clone)]
+pub struct Feature {
+    pub id: Option<u64>,
+    pub geom: Geom,
+    pub properties: Vec<(String, Value)>,
+}
+
+impl Feature {
+    pub fn new(geom: Geom) -> Feature {
+        Feature {
+            id: None,
+            geom,
+            properties: Vec::new(),
+        }
+    }
+
+    pub fn with_id(mut self, id: u64) -> Feature {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_property(mut self, key: impl Into<String>, value: Value) -> Feature {
+        self.properties.push((key.into(), value));
+        self
+    }
+}
+
+/// A property value, with one variant per value type in the spec.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    String(String),
+    Float(f32),
+    Double(f64),
+    Int(i64),
+    Uint(u64),
+    /// An integer stored with zig-zag encoding, which is smaller for negative
+    /// numbers than [`Value::Int`].
+    Sint(i64),
+    Bool(bool),
+}
+
+/// Why a tile could not be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The data ended in the middle of a field.
+    UnexpectedEof,
+    /// Protobuf groups and unknown wire types are not supported.
+    UnsupportedWireType(u8),
+    InvalidUtf8,
+    /// A feature refers to a key or value which its layer does not have.
+    InvalidTag(u32),
+    /// A geometry command other than MoveTo, LineTo or ClosePath, or one
+    /// which is missing its parameters.
+    InvalidCommand(u32),
+    /// A polygon starts with a hole.
+    HoleWithoutExterior,
+    /// A geometry command moves the cursor beyond the range of a 32 bit
+    /// coordinate.
+    CoordinateOverflow,
+    /// A layer's extent is 0 or does not fit in 32 bits.
+    InvalidExtent(u64),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEof => write!(f, "unexpected end of data"),
+            DecodeError::UnsupportedWireType(wire_type) => write!(f, "unsupported wire type {wire_type}"),
+            DecodeError::InvalidUtf8 => write!(f, "string is not valid UTF-8"),
+            DecodeError::InvalidTag(tag) => write!(f, "feature tag {tag} is out of range"),
+            DecodeError::InvalidCommand(command) => write!(f, "invalid geometry command {command}"),
+            DecodeError::HoleWithoutExterior => write!(f, "polygon starts with a hole"),
+            DecodeError::CoordinateOverflow => write!(f, "geometry coordinate out of range"),
+            DecodeError::InvalidExtent(extent) => write!(f, "invalid layer extent {extent}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encode `layers` as the tile `tile`.
+///
+/// Geometries are clipped to the tile extended by `buffer` tile coordinate
+/// units on every side. Geometry collections are split into one feature each
+/// for their points, lines and polygons, which share the collection's id and
+/// properties. Features with nothing left inside the tile are left out.
+pub fn encode_tile(tile: Tile, layers: &[Layer], buffer: u32) -> Vec<u8> {
+    let mut out = Writer::default();
+    for layer in layers {
+        out.bytes_field(3, &encode_layer(tile, layer, buffer));
+    }
+    out.buf
+}
+
+/// Decode the layers of the tile `tile` from `data`.
+///
+/// Each feature's geometry is a point, linestring or polygon when it has a
+/// single part, and the multi variant otherwise. Polygons come back with
+/// counter-clockwise exteriors, as in RFC 7946.
+pub fn decode_tile(tile: Tile, data: &[u8]) -> Result<Vec<Layer>, DecodeError> {
+    let mut reader = Reader::new(data);
+    let mut layers = Vec::new();
+    while let Some((field, value)) = reader.next_field()? {
+        if let (3, Field::Bytes(bytes)) = (field, value) {
+            layers.push(decode_layer(tile, bytes)?);
+        }
+    }
+    Ok(layers)
+}
+
+// Geometry types in the spec.
+const POINT: u64 = 1;
+const LINESTRING: u64 = 2;
+const POLYGON: u64 = 3;
+
+// Geometry commands in the spec.
+const MOVE_TO: u32 = 1;
+const LINE_TO: u32 = 2;
+const CLOSE_PATH: u32 = 7;
+
+const VERSION: u64 = 2;
+
+type TilePoint = [i32; 2];
+
+/// The parts of a geometry in integer tile coordinates, grouped by type.
+#[derive(Default)]
+struct Parts {
+    points: Vec<TilePoint>,
+    lines: Vec<Vec<TilePoint>>,
+    /// Closed rings without the closing point, exteriors with positive area
+    /// followed by their holes with negative area.
+    rings: Vec<Vec<TilePoint>>,
+}
+
+/// Conversions between lon/lat and tile coordinates.
+#[derive(Clone, Copy)]
+struct TileTransform {
+    min: Point,
+    max: Point,
+    scale: f64,
+}
+
+impl TileTransform {
+    fn new(tile: Tile, extent: u32) -> TileTransform {
+        let rect = tile.web_mercator_rect();
+        TileTransform {
+            min: rect.min(),
+            max: rect.max(),
+            scale: extent as f64 / (rect.max().x - rect.min().x),
+        }
+    }
+
+    /// From lon/lat to (unrounded) tile coordinates, with y pointing down.
+    fn to_tile(self, point: Point) -> Point {
+        let point = lonlat_to_web_mercator(point);
+        Point::new((point.x - self.min.x) * self.scale, (self.max.y - point.y) * self.scale)
+    }
+
+    fn to_lonlat(self, [x, y]: TilePoint) -> Point {
+        web_mercator_to_lonlat(Point::new(
+            self.min.x + x as f64 / self.scale,
+            self.max.y - y as f64 / self.scale,
+        ))
+    }
+}
+
+fn encode_layer(tile: Tile, layer: &Layer, buffer: u32) -> Vec<u8> {
+    let transform = TileTransform::new(tile, layer.extent);
+    let (low, high) = (-(buffer as f64), layer.extent as f64 + buffer as f64);
+    let clip = Rect::new(Point::new(low, low), Point::new(high, high));
+
+    let mut keys: Vec<&str> = Vec::new();
+    let mut key_indexes: HashMap<&str, u32> = HashMap::new();
+    let mut values: Vec<Vec<u8>> = Vec::new();
+    let mut value_indexes: HashMap<Vec<u8>, u32> = HashMap::new();
+
+    let mut out = Writer::default();
+    out.uint_field(15, VERSION);
+    out.bytes_field(1, layer.name.as_bytes());
+    for feature in &layer.features {
+        let geom = feature
+            .geom
+            .map_points(|point| transform.to_tile(point))
+            .clip_to_rect(clip);
+        let mut parts = Parts::default();
+        collect_parts(&geom, &mut parts);
+
+        let mut tags = Vec::with_capacity(feature.properties.len() * 2);
+        for (key, value) in &feature.properties {
+            let key_index = *key_indexes.entry(key).or_insert_with(|| {
+                keys.push(key);
+                keys.len() as u32 - 1
+            });
+            let value = encode_value(value);
+            let value_index = match value_indexes.get(&value) {
+                Some(&index) => index,
+                None => {
+                    let index = values.len() as u32;
+                    values.push(value.clone());
+                    value_indexes.insert(value, index);
+                    index
+                }
+            };
+            tags.extend([key_index, value_index]);
+        }
+
+        let geometries = [
+            (POINT, encode_points(&parts.points)),
+            (LINESTRING, encode_paths(&parts.lines, false)),
+            (POLYGON, encode_paths(&parts.rings, true)),
+        ];
+        for (geom_type, commands) in geometries {
+            if commands.is_empty() {
+                continue;
+            }
+            let mut encoded = Writer::default();
+            if let Some(id) = feature.id {
+                encoded.uint_field(1, id);
+            }
+            encoded.packed_field(2, &tags);
+            encoded.uint_field(3, geom_type);
+            encoded.packed_field(4, &commands);
+            out.bytes_field(2, &encoded.buf);
+        }
+    }
+    for key in keys {
+        out.bytes_field(3, key.as_bytes());
+    }
+    for value in values {
+        out.bytes_field(4, &value);
+    }
+    out.uint_field(5, layer.extent.into());
+    out.buf
+}
+
+fn encode_value(value: &Value) -> Vec<u8> {
+    let mut out = Writer::default();
+    match value {
+        Value::String(s) => out.bytes_field(1, s.as_bytes()),
+        Value::Float(f) => {
+            out.key(2, WIRE_FIXED32);
+            out.buf.extend(f.to_le_bytes());
+        }
+        Value::Double(d) => {
+            out.key(3, WIRE_FIXED64);
+            out.buf.extend(d.to_le_bytes());
+        }
+        Value::Int(i) => out.uint_field(4, *i as u64),
+        Value::Uint(u) => out.uint_field(5, *u),
+        Value::Sint(i) => out.uint_field(6, ((i << 1) ^ (i >> 63)) as u64),
+        Value::Bool(b) => out.uint_field(7, (*b).into()),
+    }
+    out.buf
+}
+
+/// Sort the parts of `geom`, which is in tile coordinates, by type and snap
+/// them to the grid.
+fn collect_parts(geom: &Geom, parts: &mut Parts) {
+    if geom.is_empty() {
+        return;
+    }
+    match geom.geom_type() {
+        GeomType::Point => parts.points.push(snap(geom.point())),
+        GeomType::MultiPoint => parts
+            .points
+            .extend((0..geom.num_points()).filter_map(|i| geom.point_at(i)).map(snap)),
+        GeomType::LineString => parts.add_line(geom.line()),
+        GeomType::MultiLineString => {
+            for i in 0..geom.num_lines() {
+                parts.add_line(geom.line_at(i));
+            }
+        }
+        GeomType::Polygon => parts.add_poly(geom.poly()),
+        GeomType::MultiPolygon => {
+            for i in 0..geom.num_polys() {
+                parts.add_poly(geom.poly_at(i));
+            }
+        }
+        GeomType::GeometryCollection => {
+            for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                collect_parts(&child, parts);
+            }
+        }
+    }
+}
+
+impl Parts {
+    fn add_line(&mut self, line: Option<Line>) {
+        let Some(line) = line else { return };
+        let points = snap_all(line.points());
+        if points.len() >= 2 {
+            self.lines.push(points);
+        }
+    }
+
+    fn add_poly(&mut self, poly: Option<Poly>) {
+        let Some(poly) = poly else { return };
+        // The exterior must wind clockwise on screen, which is a positive
+        // area with y pointing down, and the holes the other way.
+        let Some(exterior) = snap_ring(&poly.exterior(), true) else {
+            return;
+        };
+        self.rings.push(exterior);
+        self.rings
+            .extend(poly.holes().iter().filter_map(|hole| snap_ring(hole, false)));
+    }
+}
+
+fn snap(point: Point) -> TilePoint {
+    [point.x.round() as i32, point.y.round() as i32]
+}
+
+fn snap_all(points: &[Point]) -> Vec<TilePoint> {
+    let mut snapped: Vec<TilePoint> = points.iter().copied().map(snap).collect();
+    snapped.dedup();
+    snapped
+}
+
+/// The ring snapped to the grid and wound to have a positive area if
+/// `positive`, or `None` if it collapses.
+fn snap_ring(ring: &Ring, positive: bool) -> Option<Vec<TilePoint>> {
+    let mut points = snap_all(ring.vertices());
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let area = signed_area(&points);
+    if points.len() < 3 || area == 0 {
+        return None;
+    }
+    if (area > 0) != positive {
+        points.reverse();
+    }
+    Some(points)
+}
+
+/// Twice the signed area of the open ring, positive when it winds clockwise
+/// with y pointing down.
+fn signed_area(points: &[TilePoint]) -> i64 {
+    let Some(&last) = points.last() else { return 0 };
+    let mut prev = last;
+    let mut sum = 0;
+    for &point in points {
+        sum += prev[0] as i64 * point[1] as i64 - point[0] as i64 * prev[1] as i64;
+        prev = point;
+    }
+    sum
+}
+
+fn command(id: u32, count: usize) -> u32 {
+    id | (count as u32) << 3
+}
+
+fn zigzag(n: i32) -> u32 {
+    ((n << 1) ^ (n >> 31)) as u32
+}
+
+fn unzigzag(n: u32) -> i32 {
+    (n >> 1) as i32 ^ -((n & 1) as i32)
+}
+
+fn encode_points(points: &[TilePoint]) -> Vec<u32> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let mut commands = vec![command(MOVE_TO, points.len())];
+    let mut cursor = [0, 0];
+    for &point in points {
+        push_delta(&mut commands, &mut cursor, point);
+    }
+    commands
+}
+
+/// Encode lines, or rings if `close`. The cursor carries over from one part
+/// to the next.
+fn encode_paths(paths: &[Vec<TilePoint>], close: bool) -> Vec<u32> {
+    let mut commands = Vec::new();
+    let mut cursor = [0, 0];
+    for path in paths {
+        commands.push(command(MOVE_TO, 1));
+        push_delta(&mut commands, &mut cursor, path[0]);
+        commands.push(command(LINE_TO, path.len() - 1));
+        for &point in &path[1..] {
+            push_delta(&mut commands, &mut cursor, point);
+        }
+        if close {
+            commands.push(command(CLOSE_PATH, 1));
+        }
+    }
+    commands
+}
+
+fn push_delta(commands: &mut Vec<u32>, cursor: &mut TilePoint, point: TilePoint) {
+    commands.push(zigzag(point[0] - cursor[0]));
+    commands.push(zigzag(point[1] - cursor[1]));
+    *cursor = point;
+}
+
+fn decode_layer(tile: Tile, data: &[u8]) -> Result<Layer, DecodeError> {
+    let mut reader = Reader::new(data);
+    let mut name = String::new();
+    let mut extent = DEFAULT_EXTENT;
+    let mut raw_features = Vec::new();
+    let mut keys = Vec::new();
+    let mut values = Vec::new();
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, Field::Bytes(bytes)) => name = decode_string(bytes)?,
+            (2, Field::Bytes(bytes)) => raw_features.push(bytes),
+            (3, Field::Bytes(bytes)) => keys.push(decode_string(bytes)?),
+            (4, Field::Bytes(bytes)) => values.push(decode_value(bytes)?),
+            (5, Field::Varint(v)) => {
+                extent = u32::try_from(v)
+                    .ok()
+                    .filter(|&extent| extent > 0)
+                    .ok_or(DecodeError::InvalidExtent(v))?;
+            }
+            _ => {}
+        }
+    }
+    let transform = TileTransform::new(tile, extent);
+    let mut features = Vec::with_capacity(raw_features.len());
+    for bytes in raw_features {
+        if let Some(feature) = decode_feature(transform, bytes, &keys, &values)? {
+            features.push(feature);
+        }
+    }
+    Ok(Layer {
+        name,
+        extent,
+        features,
+    })
+}
+
+fn decode_string(bytes: &[u8]) -> Result<String, DecodeError> {
+    String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+fn decode_value(data: &[u8]) -> Result<Value, DecodeError> {
+    let mut reader = Reader::new(data);
+    let mut value = None;
+    while let Some((field, field_value)) = reader.next_field()? {
+        value = match (field, field_value) {
+            (1, Field::Bytes(bytes)) => Some(Value::String(decode_string(bytes)?)),
+            (2, Field::Fixed32(bytes)) => Some(Value::Float(f32::from_le_bytes(bytes))),
+            (3, Field::Fixed64(bytes)) => Some(Value::Double(f64::from_le_bytes(bytes))),
+            (4, Field::Varint(v)) => Some(Value::Int(v as i64)),
+            (5, Field::Varint(v)) => Some(Value::Uint(v)),
+            (6, Field::Varint(v)) => Some(Value::Sint((v >> 1) as i64 ^ -((v & 1) as i64))),
+            (7, Field::Varint(v)) => Some(Value::Bool(v != 0)),
+            _ => value,
+        };
+    }
+    // A value without any of the known fields is an empty string.
+    Ok(value.unwrap_or(Value::String(String::new())))
+}
+
+/// Decode a feature, or `None` if it has an unknown geometry type or no
+/// geometry.
+fn decode_feature(
+    transform: TileTransform,
+    data: &[u8],
+    keys: &[String],
+    values: &[Value],
+) -> Result<Option<Feature>, DecodeError> {
+    let mut reader = Reader::new(data);
+    let mut id = None;
+    let mut tags = Vec::new();
+    let mut geom_type = 0;
+    let mut commands = Vec::new();
+    while let Some((field, value)) = reader.next_field()? {
+        match (field, value) {
+            (1, Field::Varint(v)) => id = Some(v),
+            (2, Field::Bytes(bytes)) => tags = Reader::new(bytes).packed()?,
+            (3, Field::Varint(v)) => geom_type = v,
+            (4, Field::Bytes(bytes)) => commands = Reader::new(bytes).packed()?,
+            _ => {}
+        }
+    }
+    let mut properties = Vec::with_capacity(tags.len() / 2);
+    for pair in tags.chunks(2) {
+        let &[key, value] = pair else {
+            return Err(DecodeError::InvalidTag(pair[0]));
+        };
+        let key = keys.get(key as usize).ok_or(DecodeError::InvalidTag(key))?;
+        let value = values.get(value as usize).ok_or(DecodeError::InvalidTag(value))?;
+        properties.push((key.clone(), value.clone()));
+    }
+    let paths = decode_commands(&commands)?;
+    let lonlat = |path: &[TilePoint]| path.iter().map(|&p| transform.to_lonlat(p)).collect::<Vec<_>>();
+    let geom = match geom_type {
+        POINT => {
+            let points: Vec<Point> = paths.iter().flat_map(|path| lonlat(path)).collect();
+            match points[..] {
+                [] => return Ok(None),
+                [point] => point.geom(),
+                _ => Geom::new_multipoint(&points),
+            }
+        }
+        LINESTRING => {
+            let lines: Vec<Line> = paths.iter().map(|path| Line::new(&lonlat(path))).collect();
+            match &lines[..] {
+                [] => return Ok(None),
+                [line] => line.geom(),
+                _ => Geom::new_multilinestring(&lines),
+            }
+        }
+        POLYGON => {
+            let polys = decode_polys(&paths, lonlat)?;
+            match &polys[..] {
+                [] => return Ok(None),
+                [poly] => poly.geom(),
+                _ => Geom::new_multipolygon(&polys),
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(Feature { id, geom, properties }))
+}
+
+/// Split a command stream into its paths, in tile coordinates.
+fn decode_commands(commands: &[u32]) -> Result<Vec<Vec<TilePoint>>, DecodeError> {
+    let mut paths: Vec<Vec<TilePoint>> = Vec::new();
+    let mut cursor = [0, 0];
+    let mut i = 0;
+    while i < commands.len() {
+        let command = commands[i];
+        let (id, count) = (command & 0x7, (command >> 3) as usize);
+        i += 1;
+        match id {
+            MOVE_TO | LINE_TO => {
+                let params = commands
+                    .get(i..i + 2 * count)
+                    .ok_or(DecodeError::InvalidCommand(command))?;
+                i += 2 * count;
+                for delta in params.chunks(2) {
+                    let step = |from: i32, delta: u32| from.checked_add(unzigzag(delta));
+                    cursor = match (step(cursor[0], delta[0]), step(cursor[1], delta[1])) {
+                        (Some(x), Some(y)) => [x, y],
+                        _ => return Err(DecodeError::CoordinateOverflow),
+                    };
+                    match (id, paths.last_mut()) {
+                        (LINE_TO, Some(path)) => path.push(cursor),
+                        (LINE_TO, None) => return Err(DecodeError::InvalidCommand(command)),
+                        _ => paths.push(vec![cursor]),
+                    }
+                }
+            }
+            CLOSE_PATH => {}
+            _ => return Err(DecodeError::InvalidCommand(command)),
+        }
+    }
+    Ok(paths)
+}
+
+/// Group rings into polygons: each ring with a positive area starts a new
+/// polygon, and each with a negative area is a hole in the previous one.
+fn decode_polys(
+    rings: &[Vec<TilePoint>],
+    lonlat: impl Fn(&[TilePoint]) -> Vec<Point>,
+) -> Result<Vec<Poly>, DecodeError> {
+    let mut polys: Vec<(Ring, Vec<Ring>)> = Vec::new();
+    for ring in rings {
+        let area = signed_area(ring);
+        if area == 0 {
+            continue;
+        }
+        // Exteriors wind clockwise on screen in tile coordinates, and so they
+        // do in lon/lat too. Reverse them for RFC 7946.
+        let mut points = lonlat(ring);
+        points.push(points[0]);
+        points.reverse();
+        let ring = Ring::new(&points);
+        match polys.last_mut() {
+            _ if area > 0 => polys.push((ring, Vec::new())),
+            Some((_, holes)) => holes.push(ring),
+            None => return Err(DecodeError::HoleWithoutExterior),
+        }
+    }
+    Ok(polys
+        .iter()
+        .map(|(exterior, holes)| Poly::new(exterior, holes))
+        .collect())
+}
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_FIXED64: u8 = 1;
+const WIRE_BYTES: u8 = 2;
+const WIRE_FIXED32: u8 = 5;
+
+/// A minimal protobuf writer.
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn varint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.buf.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.buf.push(v as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(u64::from(field << 3 | u32::from(wire_type)));
+    }
+
+    fn uint_field(&mut self, field: u32, v: u64) {
+        self.key(field, WIRE_VARINT);
+        self.varint(v);
+    }
+
+    fn bytes_field(&mut self, field: u32, bytes: &[u8]) {
+        self.key(field, WIRE_BYTES);
+        self.varint(bytes.len() as u64);
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// A packed repeated field, left out when empty.
+    fn packed_field(&mut self, field: u32, values: &[u32]) {
+        if values.is_empty() {
+            return;
+        }
+        let mut packed = Writer::default();
+        for &v in values {
+            packed.varint(v.into());
+        }
+        self.bytes_field(field, &packed.buf);
+    }
+}
+
+/// A field value as read from the wire.
+enum Field<'a> {
+    Varint(u64),
+    Fixed64([u8; 8]),
+    Bytes(&'a [u8]),
+    Fixed32([u8; 4]),
+}
+
+/// A minimal protobuf reader.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Reader<'a> {
+        Reader { data }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if len > self.data.len() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut v = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            v |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(DecodeError::UnexpectedEof)
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u32, Field<'a>)>, DecodeError> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let field = (key >> 3) as u32;
+        let value = match (key & 0x7) as u8 {
+            WIRE_VARINT => Field::Varint(self.varint()?),
+            WIRE_FIXED64 => Field::Fixed64(self.take(8)?.try_into().expect("took 8 bytes")),
+            WIRE_BYTES => {
+                let len = self.varint()?;
+                Field::Bytes(self.take(len.try_into().map_err(|_| DecodeError::UnexpectedEof)?)?)
+            }
+            WIRE_FIXED32 => Field::Fixed32(self.take(4)?.try_into().expect("took 4 bytes")),
+            wire_type => return Err(DecodeError::UnsupportedWireType(wire_type)),
+        };
+        Ok(Some((field, value)))
+    }
+
+    /// The rest of the data as packed `uint32`s.
+    fn packed(mut self) -> Result<Vec<u32>, DecodeError> {
+        let mut values = Vec::new();
+        while !self.data.is_empty() {
+            values.push(self.varint()? as u32);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_commands, decode_tile, encode_paths, encode_points, encode_tile, unzigzag, zigzag,
+        DecodeError, Feature, Layer, Value,
+    };
+    use crate::test_util::square;
+    use crate::{tiles::Tile, Geom, GeomType, Line, Point, Poly};
+
+    const TILE: Tile = Tile { z: 1, x: 1, y: 0 };
+
+    fn near(a: Point, b: Point) -> bool {
+        (a.x - b.x).abs() < 0.1 && (a.y - b.y).abs() < 0.1
+    }
+
+    #[test]
+    fn zigzag_encoding() {
+        for (n, encoded) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (i32::MAX, u32::MAX - 1), (i32::MIN, u32::MAX)] {
+            assert_eq!(zigzag(n), encoded);
+            assert_eq!(unzigzag(encoded), n);
+        }
+    }
+
+    #[test]
+    fn command_encoding() {
+        // The examples from the spec.
+        assert_eq!(encode_points(&[[25, 17]]), [9, 50, 34]);
+        assert_eq!(encode_points(&[[5, 7], [3, 2]]), [17, 10, 14, 3, 9]);
+        assert_eq!(encode_paths(&[vec![[2, 2], [2, 10], [10, 10]]], false), [9, 4, 4, 18, 0, 16, 16, 0]);
+        let rings = [vec![[3, 6], [8, 12], [20, 34]]];
+        assert_eq!(encode_paths(&rings, true), [9, 6, 12, 18, 10, 12, 24, 44, 15]);
+        assert_eq!(decode_commands(&[9, 6, 12, 18, 10, 12, 24, 44, 15]), Ok(rings.to_vec()));
+    }
+
+    #[test]
+    fn single_point() {
+        let layer = Layer::new("points").with_feature(Feature::new(Point::new(90., 45.).geom()));
+        let bytes = encode_tile(Tile::new(0, 0, 0), &[layer], 0);
+        let layers = decode_tile(Tile::new(0, 0, 0), &bytes).unwrap();
+        assert_eq!(layers[0].features[0].geom.geom_type(), GeomType::Point);
+        assert!(near(layers[0].features[0].geom.point(), Point::new(90., 45.)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let hole = square(40., 30., 10.);
+        let poly = Poly::new(&square(20., 20., 60.), &[hole.reversed()]).geom();
+        let line = Line::new(&[Point::new(10., 10.), Point::new(50., 60.), Point::new(50., -60.)]).geom();
+        let layer = Layer::new("things")
+            .with_feature(
+                Feature::new(poly)
+                    .with_id(7)
+                    .with_property("name", Value::String("park".into()))
+                    .with_property("area", Value::Double(1.5)),
+            )
+            .with_feature(
+                Feature::new(line)
+                    .with_property("name", Value::String("road".into()))
+                    .with_property("lanes", Value::Sint(-2)),
+            )
+            .with_feature(Feature::new(Point::new(-90., 10.).geom()));
+        let bytes = encode_tile(TILE, &[layer], 64);
+        let layers = decode_tile(TILE, &bytes).unwrap();
+        assert_eq!(layers.len(), 1);
+        let layer = &layers[0];
+        assert_eq!(layer.name, "things");
+        assert_eq!(layer.extent, 4096);
+        // the point is in another tile
+        assert_eq!(layer.features.len(), 2);
+
+        let poly = &layer.features[0];
+        assert_eq!(poly.id, Some(7));
+        assert_eq!(
+            poly.properties,
+            vec![
+                ("name".to_string(), Value::String("park".into())),
+                ("area".to_string(), Value::Double(1.5)),
+            ]
+        );
+        let decoded = poly.geom.poly().unwrap();
+        assert!(!decoded.exterior().clockwise());
+        assert!(decoded.hole(0).unwrap().clockwise());
+        assert!(near(decoded.exterior().rect().min(), Point::new(20., 20.)));
+        assert!(near(decoded.hole(0).unwrap().rect().max(), Point::new(50., 40.)));
+
+        let line = &layer.features[1];
+        assert_eq!(line.properties[1], ("lanes".to_string(), Value::Sint(-2)));
+        let decoded = line.geom.line().unwrap();
+        assert_eq!(decoded.num_points(), 3);
+        assert!(near(decoded.point(1).unwrap(), Point::new(50., 60.)));
+        // clipped to the tile and its buffer of 64 / 4096 of 180°
+        let end = decoded.point(2).unwrap();
+        assert!(near(end, Point::new(50., -2.81)), "{end:?}");
+    }
+
+    #[test]
+    fn collections_and_multis() {
+        let collection = Geom::new_geometrycollection(&[
+            Geom::new_multipoint(&[Point::new(10., 10.), Point::new(20., 20.)]),
+            Poly::from(square(30., 30., 10.)).geom(),
+            Poly::from(square(60., 30., 10.)).geom(),
+        ]);
+        let layer = Layer::new("mixed").with_feature(Feature::new(collection).with_id(1));
+        let layers = decode_tile(TILE, &encode_tile(TILE, &[layer], 0)).unwrap();
+        let features = &layers[0].features;
+        assert_eq!(features.len(), 2);
+        assert!(features.iter().all(|feature| feature.id == Some(1)));
+        assert_eq!(features[0].geom.geom_type(), GeomType::MultiPoint);
+        assert_eq!(features[1].geom.geom_type(), GeomType::MultiPolygon);
+        assert_eq!(features[1].geom.num_polys(), 2);
+    }
+
+    #[test]
+    fn tiny_geometries_are_dropped() {
+        let speck = Poly::from(square(10., 10., 1e-6)).geom();
+        let layer = Layer::new("specks").with_feature(Feature::new(speck));
+        let layers = decode_tile(TILE, &encode_tile(TILE, &[layer], 0)).unwrap();
+        assert!(layers[0].features.is_empty());
+    }
+
+    #[test]
+    fn malformed_tiles() {
+        assert_eq!(decode_tile(TILE, &[]).unwrap().len(), 0);
+        assert!(matches!(decode_tile(TILE, &[0x1a, 0x05, 0x0a]), Err(DecodeError::UnexpectedEof)));
+        assert!(matches!(decode_tile(TILE, &[0x1b]), Err(DecodeError::UnsupportedWireType(3))));
+        // a layer with one feature whose geometry is LineTo without MoveTo
+        let feature = [0x18, 0x02, 0x22, 0x03, 0x0a, 0x02, 0x02];
+        let mut layer = vec![0x12, feature.len() as u8];
+        layer.extend(feature);
+        let mut tile = vec![0x1a, layer.len() as u8];
+        tile.extend(layer);
+        assert!(matches!(decode_tile(TILE, &tile), Err(DecodeError::InvalidCommand(10))));
+        // two moves of i32::MAX each
+        let commands = [2 << 3 | 1, 0xFFFFFFFE, 0xFFFFFFFE, 0xFFFFFFFE, 0xFFFFFFFE];
+        assert_eq!(decode_commands(&commands), Err(DecodeError::CoordinateOverflow));
+        // layers with an extent of 0 and of 2^32
+        assert!(matches!(decode_tile(TILE, &[0x1a, 0x02, 0x28, 0x00]), Err(DecodeError::InvalidExtent(0))));
+        let tile = [0x1a, 0x06, 0x28, 0x80, 0x80, 0x80, 0x80, 0x10];
+        assert!(matches!(decode_tile(TILE, &tile), Err(DecodeError::InvalidExtent(0x1_0000_0000))));
+    }
+
+    #[test]
+    #[should_panic(expected = "extent must be positive")]
+    fn zero_extent() {
+        Layer::new("empty").with_extent(0);
+    }
+}
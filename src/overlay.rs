@@ -0,0 +1,408 @@
+//! Boolean operations between polygons: intersection, union, difference and
+//! symmetric difference.
+//!
+//! The boundaries of both polygons are wound with their interiors on the
+//! left, then split wherever they meet, with candidate segment pairs found
+//! by [`Ring::ring_search`]. Each piece lies entirely inside or outside the
+//! other polygon, or runs along its boundary in the same or the opposite
+//! direction. The operation keeps some of the pieces, reversing some, and
+//! links them back up into rings at the points where they meet.
+//!
+//! The polygons are expected to be valid. Points where the pieces meet are
+//! shared exactly between them, but the crossings themselves are computed in
+//! floating point, so nearly coincident edges can give slivers.
+
+use crate::{
+    point::cross,
+    polygonize::{link, polys_from_rings},
+    Geom, Point, Poly, Ring, Segment, Winding,
+};
+
+/// Overlay
+impl Poly {
+    /// The area covered by both polygons, as a multipolygon.
+    pub fn intersection(&self, other: &Poly) -> Geom {
+        overlay(self, other, Operation::Intersection)
+    }
+
+    /// The area covered by either polygon, as a multipolygon.
+    pub fn union(&self, other: &Poly) -> Geom {
+        overlay(self, other, Operation::Union)
+    }
+
+    /// The area covered by `self` but not `other`, as a multipolygon.
+    pub fn difference(&self, other: &Poly) -> Geom {
+        overlay(self, other, Operation::Difference)
+    }
+
+    /// The area covered by exactly one of the polygons, as a multipolygon.
+    pub fn sym_difference(&self, other: &Poly) -> Geom {
+        overlay(self, other, Operation::SymDifference)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Operation {
+    Intersection,
+    Union,
+    Difference,
+    SymDifference,
+}
+
+/// Where a piece of one polygon's boundary lies relative to the other
+/// polygon.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Class {
+    Inside,
+    Outside,
+    /// Along the other boundary, with the interiors on the same side.
+    Same,
+    /// Along the other boundary, with the interiors on opposite sides.
+    Opposite,
+}
+
+impl Operation {
+    /// Whether a piece of the first (`first`) or second polygon's boundary
+    /// is kept, and if so whether it is reversed.
+    fn keep(self, first: bool, class: Class) -> Option<bool> {
+        use Class::*;
+        match (self, first, class) {
+            (Operation::Intersection, _, Inside) | (Operation::Intersection, true, Same) => Some(false),
+            (Operation::Union, _, Outside) | (Operation::Union, true, Same) => Some(false),
+            (Operation::Difference, true, Outside | Opposite) => Some(false),
+            (Operation::Difference, false, Inside) => Some(true),
+            (Operation::SymDifference, _, Outside) => Some(false),
+            (Operation::SymDifference, _, Inside) => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// The boundary of a polygon, with its interior on the left, and the points
+/// where each segment is split.
+struct Boundary {
+    poly: Poly,
+    rings: Vec<Ring>,
+    segments: Vec<Segment>,
+    /// The fraction along each segment and the point of each split.
+    splits: Vec<Vec<(f64, Point)>>,
+    /// The fractions along each segment where it runs along the other
+    /// boundary, and whether in the same direction.
+    overlaps: Vec<Vec<(f64, f64, bool)>>,
+}
+
+impl Boundary {
+    fn new(poly: &Poly) -> Boundary {
+        let poly = poly.normalize_winding(Winding::Rfc7946);
+        let rings: Vec<Ring> = std::iter::once(poly.exterior()).chain(poly.holes()).collect();
+        let segments: Vec<Segment> = rings
+            .iter()
+            .flat_map(|ring| (0..ring.num_segments()).filter_map(|i| ring.segment(i)))
+            .collect();
+        let splits = segments.iter().map(|s| vec![(0., s.a()), (1., s.b())]).collect();
+        let overlaps = segments.iter().map(|_| Vec::new()).collect();
+        Boundary {
+            poly,
+            rings,
+            segments,
+            splits,
+            overlaps,
+        }
+    }
+
+    /// The index of the first segment of each ring.
+    fn offsets(&self) -> Vec<usize> {
+        self.rings
+            .iter()
+            .scan(0, |offset, ring| {
+                let start = *offset;
+                *offset += ring.num_segments();
+                Some(start)
+            })
+            .collect()
+    }
+
+    fn split(&mut self, index: usize, point: Point) {
+        let fraction = fraction(self.segments[index], point).clamp(0., 1.);
+        self.splits[index].push((fraction, point));
+    }
+
+    /// The pieces of the boundary between its splits, classified against
+    /// the other polygon.
+    fn pieces(&self, other: &Poly) -> Vec<(Point, Point, Class)> {
+        let mut pieces = Vec::new();
+        for (splits, overlaps) in self.splits.iter().zip(&self.overlaps) {
+            let mut splits = splits.clone();
+            splits.sort_by(|x, y| x.0.total_cmp(&y.0));
+            splits.dedup_by(|x, y| x.1 == y.1);
+            for pair in splits.windows(2) {
+                let ((start, a), (end, b)) = (pair[0], pair[1]);
+                let along = overlaps
+                    .iter()
+                    .find(|&&(from, to, _)| from <= start && end <= to);
+                let class = match along {
+                    Some(&(_, _, true)) => Class::Same,
+                    Some(_) => Class::Opposite,
+                    None if other.intersects_point(Point::new((a.x + b.x) / 2., (a.y + b.y) / 2.)) => {
+                        Class::Inside
+                    }
+                    None => Class::Outside,
+                };
+                pieces.push((a, b, class));
+            }
+        }
+        pieces
+    }
+}
+
+/// How far along `segment` the projection of `point` is, as a fraction of
+/// its length.
+pub(crate) fn fraction(segment: Segment, point: Point) -> f64 {
+    let (a, b) = (segment.a(), segment.b());
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len2 = dx * dx + dy * dy;
+    if len2 == 0. {
+        0.
+    } else {
+        ((point.x - a.x) * dx + (point.y - a.y) * dy) / len2
+    }
+}
+
+/// The union of all the polygons, as polygons which only touch at points.
+///
+/// Halves are merged recursively, so each overlay works on pieces of similar
+/// size.
+pub(crate) fn union_all(mut polys: Vec<Poly>) -> Vec<Poly> {
+    if polys.len() <= 1 {
+        return polys;
+    }
+    let right = union_all(polys.split_off(polys.len() / 2));
+    let mut merged = union_all(polys);
+    for poly in right {
+        let mut poly = poly;
+        let mut i = 0;
+        while i < merged.len() {
+            let other = &merged[i];
+            if other.rect().intersects_rect(poly.rect()) && other.geom().intersects(&poly.geom()) {
+                let mut union = overlay_polys(&poly, other, Operation::Union);
+                if union.len() == 1 {
+                    poly = union.pop().expect("one polygon");
+                    merged.swap_remove(i);
+                    // the grown polygon may reach ones already passed over
+                    i = 0;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        merged.push(poly);
+    }
+    merged
+}
+
+fn overlay(a: &Poly, b: &Poly, operation: Operation) -> Geom {
+    Geom::new_multipolygon(&overlay_polys(a, b, operation))
+}
+
+fn overlay_polys(a: &Poly, b: &Poly, operation: Operation) -> Vec<Poly> {
+    let (mut first, mut second) = (Boundary::new(a), Boundary::new(b));
+    split(&mut first, &mut second);
+
+    let mut edges = Vec::new();
+    for (is_first, boundary, other) in [(true, &first, &second), (false, &second, &first)] {
+        for (a, b, class) in boundary.pieces(&other.poly) {
+            match operation.keep(is_first, class) {
+                Some(false) => edges.push((a, b)),
+                Some(true) => edges.push((b, a)),
+                None => {}
+            }
+        }
+    }
+    polys_from_rings(link(&edges))
+}
+
+/// Split the segments of both boundaries where they meet.
+fn split(first: &mut Boundary, second: &mut Boundary) {
+    let (first_offsets, second_offsets) = (first.offsets(), second.offsets());
+    let mut meets = Vec::new();
+    for (ring, &i_offset) in first.rings.iter().zip(&first_offsets) {
+        for (other, &j_offset) in second.rings.iter().zip(&second_offsets) {
+            if !ring.rect().intersects_rect(other.rect()) {
+                continue;
+            }
+            ring.ring_search(other, &mut |s: Segment, i: usize, t: Segment, j: usize| {
+                if s.intersects_segment(t) {
+                    meets.push((i_offset + i, s, j_offset + j, t));
+                }
+                true
+            });
+        }
+    }
+    for (i, s, j, t) in meets {
+        if cross(s.a(), s.b(), t.a()) == 0. && cross(s.a(), s.b(), t.b()) == 0. {
+            for p in [t.a(), t.b()].into_iter().filter(|&p| s.contains_point(p)) {
+                first.split(i, p);
+            }
+            for p in [s.a(), s.b()].into_iter().filter(|&p| t.contains_point(p)) {
+                second.split(j, p);
+            }
+            let same = (s.b().x - s.a().x) * (t.b().x - t.a().x) + (s.b().y - s.a().y) * (t.b().y - t.a().y) > 0.;
+            let (u, v) = (fraction(s, t.a()), fraction(s, t.b()));
+            let (start, end) = (u.min(v).max(0.), u.max(v).min(1.));
+            if start < end {
+                first.overlaps[i].push((start, end, same));
+            }
+            let (u, v) = (fraction(t, s.a()), fraction(t, s.b()));
+            let (start, end) = (u.min(v).max(0.), u.max(v).min(1.));
+            if start < end {
+                second.overlaps[j].push((start, end, same));
+            }
+            continue;
+        }
+        // Prefer exact vertices over computed intersections, so both sides
+        // are split at the same point.
+        let point = [t.a(), t.b()]
+            .into_iter()
+            .find(|&p| s.contains_point(p))
+            .or_else(|| [s.a(), s.b()].into_iter().find(|&p| t.contains_point(p)))
+            .or_else(|| s.intersection(t));
+        if let Some(point) = point {
+            first.split(i, point);
+            second.split(j, point);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::square;
+    use crate::{Geom, GeomType, Point, Poly, Ring};
+
+    fn area(geom: &Geom) -> f64 {
+        (0..geom.num_polys())
+            .filter_map(|i| geom.poly_at(i))
+            .map(|poly| poly.exterior().area() - poly.holes().iter().map(Ring::area).sum::<f64>())
+            .sum()
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = Poly::from(square(0., 0., 10.));
+        let b = Poly::from(square(5., 5., 10.));
+
+        let both = a.intersection(&b);
+        assert_eq!(both.geom_type(), GeomType::MultiPolygon);
+        assert_eq!(both.num_polys(), 1);
+        assert!(both.eq_topo(&Poly::from(square(5., 5., 5.)).geom()));
+        assert_eq!(both.poly_at(0).unwrap().exterior().num_points(), 5);
+
+        let either = a.union(&b);
+        assert_eq!(either.num_polys(), 1);
+        assert_eq!(area(&either), 175.);
+        assert_eq!(either.poly_at(0).unwrap().exterior().num_points(), 9);
+
+        let only_a = a.difference(&b);
+        assert_eq!(area(&only_a), 75.);
+        assert!(only_a.covers(&Point::new(2., 2.).geom()));
+        assert!(!only_a.intersects(&Point::new(7., 7.).geom()));
+
+        let one = a.sym_difference(&b);
+        assert_eq!(one.num_polys(), 2);
+        assert_eq!(area(&one), 150.);
+    }
+
+    #[test]
+    fn signed_zero() {
+        let ring = |points: &[(f64, f64)]| {
+            let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+            Poly::from(Ring::new(&points))
+        };
+        // -0. and 0. are the same point where the boundaries meet
+        let a = ring(&[
+            (5., 0.),
+            (2., 1.),
+            (2., 4.),
+            (-0., 2.),
+            (-3., 3.),
+            (-4., 1.),
+            (-3., -1.),
+            (-3., -3.),
+            (-1., -5.),
+            (1., -3.),
+            (2., -2.),
+            (5., 0.),
+        ]);
+        let b = ring(&[(4., 0.), (3., 2.), (2., 5.), (0., 2.), (-3., 0.), (-2., -3.), (2., -2.), (3., -2.), (4., 0.)]);
+        let both = area(&a.intersection(&b));
+        let either = area(&a.union(&b));
+        assert!(both > 0.);
+        assert!(either > 0.);
+        let total = a.exterior().area() + b.exterior().area();
+        assert!((both + either - total).abs() < 1e-9, "{both} + {either} != {total}");
+    }
+
+    #[test]
+    fn disjoint_and_nested() {
+        let a = Poly::from(square(0., 0., 10.));
+        let far = Poly::from(square(20., 0., 5.));
+        assert!(a.intersection(&far).is_empty());
+        assert_eq!(a.union(&far).num_polys(), 2);
+        assert!(a.difference(&far).eq_topo(&a.geom()));
+
+        // cutting a hole
+        let inner = Poly::from(square(2., 2., 3.));
+        let holed = a.difference(&inner);
+        assert_eq!(holed.num_polys(), 1);
+        assert_eq!(holed.poly_at(0).unwrap().num_holes(), 1);
+        assert_eq!(area(&holed), 91.);
+        assert!(inner.difference(&a).is_empty());
+        assert!(a.union(&inner).eq_topo(&a.geom()));
+        assert!(inner.intersection(&a).eq_topo(&inner.geom()));
+
+        // and filling it back in
+        assert!(Poly::from(square(2., 2., 3.)).union(&holed.poly_at(0).unwrap()).eq_topo(&a.geom()));
+    }
+
+    #[test]
+    fn area_identities() {
+        let diamond = Poly::from(Ring::new(&[
+            Point::new(5., -2.),
+            Point::new(12., 5.),
+            Point::new(5., 12.),
+            Point::new(-2., 5.),
+            Point::new(5., -2.),
+        ]));
+        let holed = Poly::new(&square(0., 0., 10.), &[square(3., 3., 4.)]);
+        let (a, b) = (diamond.exterior().area(), 100. - 16.);
+        let both = area(&diamond.intersection(&holed));
+        assert!(both > 0. && both < a.min(b));
+        let close = |x: f64, y: f64| (x - y).abs() < 1e-9;
+        assert!(close(area(&diamond.union(&holed)), a + b - both));
+        assert!(close(area(&diamond.difference(&holed)), a - both));
+        assert!(close(area(&holed.difference(&diamond)), b - both));
+        assert!(close(area(&diamond.sym_difference(&holed)), a + b - 2. * both));
+    }
+
+    #[test]
+    fn shared_edges() {
+        let a = Poly::from(square(0., 0., 1.));
+        let b = Poly::from(square(1., 0., 1.));
+        let merged = a.union(&b);
+        assert_eq!(merged.num_polys(), 1);
+        assert_eq!(merged.poly_at(0).unwrap().exterior().num_points(), 5);
+        assert_eq!(area(&merged), 2.);
+        assert!(a.intersection(&b).is_empty());
+        assert!(a.difference(&b).eq_topo(&a.geom()));
+        assert_eq!(area(&a.sym_difference(&b)), 2.);
+        assert!(a.difference(&a).is_empty());
+        assert!(a.union(&a).eq_topo(&a.geom()));
+
+        // touching only at a corner stays two polygons
+        let corner = Poly::from(square(1., 1., 1.));
+        assert_eq!(a.union(&corner).num_polys(), 2);
+
+        // clockwise input is handled too
+        let clockwise = Poly::from(square(0.5, 0., 1.).reversed());
+        assert_eq!(area(&a.intersection(&clockwise)), 0.5);
+    }
+}
@@ -76,6 +76,21 @@ impl Point {
     }
 }
 
+/// Geometric measurements
+impl Point {
+    /// The euclidean distance between two points.
+    pub fn distance(self, other: Point) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+/// Twice the signed area of the triangle `o, a, b`: positive when the turn
+/// `o -> a -> b` is counter-clockwise, negative when clockwise, and zero when
+/// the points are collinear.
+pub(crate) fn cross(o: Point, a: Point, b: Point) -> f64 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
 impl fmt::Debug for Point {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Point")
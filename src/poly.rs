@@ -1,11 +1,216 @@
 //! Polygon type and associated code
 //!
-//! - [ ] Create type and constructors
-//! - [ ] Make Send + Sync
-//! - [ ] impl Drop
-//! - [ ] Create accessors
-//! - [ ] Add tg_sys conversions
-//! - [ ] Add Geom conversions
-//! - [ ] Add PolyFuncs
+//! - [x] Create type and constructors
+//! - [x] Make Send + Sync
+//! - [x] impl Drop
+//! - [x] Create accessors
+//! - [x] Add tg_sys conversions
+//! - [x] Add Geom conversions
+//! - [x] Add PolyFuncs
 //! - [ ] Standard traits
 //! - [ ] Documentation
+
+use std::{
+    alloc::{handle_alloc_error, Layout},
+    fmt,
+    ptr::NonNull,
+};
+
+use tg_sys::{tg_geom, tg_poly, tg_ring, GeometryConstructors, GeometryPredicates, PolyFuncs};
+
+use crate::{Geom, Point, Rect, Ring};
+
+pub struct Poly {
+    inner: NonNull<tg_poly>,
+}
+
+/// Constructors
+impl Poly {
+    pub fn new(exterior: &Ring, holes: &[Ring]) -> Poly {
+        let holes: Vec<*const tg_ring> = holes.iter().map(|hole| hole.as_raw() as *const _).collect();
+        let len = holes.len().try_into().expect("len must be a valid i32");
+        let raw = unsafe { PolyFuncs::tg_poly_new(exterior.as_raw(), holes.as_ptr(), len) };
+        if raw.is_null() {
+            Poly::handle_alloc_error();
+        }
+        unsafe { Poly::from_raw_unchecked(raw) }
+    }
+
+    pub fn as_raw(&self) -> *mut tg_poly {
+        self.inner.as_ptr()
+    }
+
+    pub fn from_raw(raw: *mut tg_poly) -> Option<Poly> {
+        Some(Poly {
+            inner: NonNull::new(raw)?,
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `raw` must point to a valid tg_poly.
+    pub unsafe fn from_raw_unchecked(raw: *mut tg_poly) -> Poly {
+        Poly {
+            inner: NonNull::new_unchecked(raw),
+        }
+    }
+
+    pub fn duplicate(&self) -> Poly {
+        let raw = unsafe { PolyFuncs::tg_poly_copy(self.as_raw()) };
+        if raw.is_null() {
+            Poly::handle_alloc_error();
+        }
+        unsafe { Poly::from_raw_unchecked(raw) }
+    }
+
+    /// Take a new reference to a polygon owned by another tg object, such as
+    /// a Polygon geometry.
+    pub(crate) fn clone_from_raw(raw: *const tg_poly) -> Poly {
+        let new = unsafe { PolyFuncs::tg_poly_clone(raw) };
+        if new.is_null() {
+            Poly::handle_alloc_error();
+        }
+        unsafe { Poly::from_raw_unchecked(new) }
+    }
+
+    pub fn geom(&self) -> Geom {
+        let raw = unsafe { GeometryConstructors::tg_geom_new_polygon(self.as_raw()) };
+        if raw.is_null() {
+            Geom::handle_alloc_error();
+        }
+        unsafe { Geom::from_raw_unchecked(raw) }
+    }
+
+    pub(crate) fn handle_alloc_error() -> ! {
+        let layout = Layout::new::<tg_poly>();
+        handle_alloc_error(layout)
+    }
+}
+
+/// Operations from PolyFuncs
+impl Poly {
+    pub fn memsize(&self) -> usize {
+        unsafe { PolyFuncs::tg_poly_memsize(self.as_raw()) }
+    }
+
+    pub fn rect(&self) -> Rect {
+        unsafe { PolyFuncs::tg_poly_rect(self.as_raw()) }.into()
+    }
+
+    /// The exterior ring of the polygon.
+    ///
+    /// The returned ring shares its storage with the polygon.
+    pub fn exterior(&self) -> Ring {
+        let ring = unsafe { PolyFuncs::tg_poly_exterior(self.as_raw()) };
+        Ring::clone_from_raw(ring)
+    }
+
+    pub fn num_holes(&self) -> usize {
+        unsafe { PolyFuncs::tg_poly_num_holes(self.as_raw()) }
+            .try_into()
+            .expect("tg_poly_num_holes should return a valid usize")
+    }
+
+    /// Get the hole at the given index.
+    ///
+    /// The returned ring shares its storage with the polygon.
+    pub fn hole(&self, index: usize) -> Option<Ring> {
+        (index < self.num_holes()).then(|| {
+            let ring = unsafe {
+                PolyFuncs::tg_poly_hole_at(
+                    self.as_raw(),
+                    index.try_into().expect("index is a valid c_int"),
+                )
+            };
+            Ring::clone_from_raw(ring)
+        })
+    }
+
+    /// All holes of the polygon, in order.
+    pub fn holes(&self) -> Vec<Ring> {
+        (0..self.num_holes()).filter_map(|i| self.hole(i)).collect()
+    }
+
+    pub fn clockwise(&self) -> bool {
+        unsafe { PolyFuncs::tg_poly_clockwise(self.as_raw()) }
+    }
+
+    /// Whether the point is inside the polygon or on its boundary.
+    pub(crate) fn intersects_point(&self, point: Point) -> bool {
+        // A tg_poly can always be upcast to a tg_geom.
+        let geom = self.as_raw() as *const tg_geom;
+        unsafe { GeometryPredicates::tg_geom_intersects_xy(geom, point.x, point.y) }
+    }
+}
+
+/// Coordinate mapping
+impl Poly {
+    /// Create a new polygon with every point of every ring passed through `f`.
+    pub fn map_points<F: FnMut(Point) -> Point>(&self, mut f: F) -> Poly {
+        let exterior = self.exterior().map_points(&mut f);
+        let holes: Vec<Ring> = self.holes().iter().map(|hole| hole.map_points(&mut f)).collect();
+        Poly::new(&exterior, &holes)
+    }
+}
+
+#[cfg(feature = "atomics")]
+unsafe impl Send for Poly {}
+#[cfg(feature = "atomics")]
+unsafe impl Sync for Poly {}
+
+impl Drop for Poly {
+    fn drop(&mut self) {
+        unsafe {
+            PolyFuncs::tg_poly_free(self.as_raw());
+        }
+    }
+}
+
+impl Clone for Poly {
+    fn clone(&self) -> Self {
+        let new = unsafe { PolyFuncs::tg_poly_clone(self.as_raw()) };
+        if new.is_null() {
+            Poly::handle_alloc_error();
+        }
+        unsafe { Poly::from_raw_unchecked(new) }
+    }
+}
+
+impl fmt::Debug for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Poly")
+            .field("exterior", &self.exterior())
+            .field("holes", &self.holes())
+            .finish()
+    }
+}
+
+impl PartialEq for Poly {
+    fn eq(&self, other: &Self) -> bool {
+        self.exterior() == other.exterior() && self.holes() == other.holes()
+    }
+}
+
+impl From<Ring> for Poly {
+    fn from(ring: Ring) -> Poly {
+        Poly::new(&ring, &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Poly;
+    use crate::test_util::square;
+
+    #[test]
+    fn exterior_and_holes() {
+        let exterior = square(0., 0., 10.);
+        let hole = square(2., 2., 2.);
+        let poly = Poly::new(&exterior, std::slice::from_ref(&hole));
+        assert_eq!(poly.exterior(), exterior);
+        assert_eq!(poly.num_holes(), 1);
+        assert_eq!(poly.hole(0), Some(hole));
+        assert_eq!(poly.hole(1), None);
+        assert_eq!(poly, poly.duplicate());
+    }
+}
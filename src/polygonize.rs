@@ -0,0 +1,394 @@
+//! Polygons assembled from loose rings and from networks of lines.
+//!
+//! Line networks are split wherever the lines meet, with candidate segment
+//! pairs found by [`Line::line_search`]. Edges which dangle from the network,
+//! or which are the only link between two parts of it, cannot bound a face
+//! and are dropped. The rest are linked into rings in both directions: each
+//! face of the network comes out counter-clockwise, and the outline of each
+//! connected part clockwise, becoming a hole of any face around it.
+
+use std::{
+    collections::{HashMap, HashSet},
+    f64::consts::TAU,
+};
+
+use crate::{overlay::fraction, point::cross, Geom, Line, Point, Poly, Ring, Segment};
+
+/// The polygons formed by a network of lines.
+///
+/// Every area fully enclosed by the lines becomes a polygon, with holes for
+/// the parts of the network inside it, which also give polygons of their
+/// own. Lines are split where they cross or touch each other. Exteriors are
+/// counter-clockwise and holes clockwise.
+pub fn polygonize(lines: &[Line]) -> Vec<Poly> {
+    let mut edges = node(lines);
+    loop {
+        remove_dangles(&mut edges);
+        let directed: Vec<(Point, Point)> = edges.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+        let rings = link(&directed);
+        // An edge which a ring runs along both ways joins two parts of the
+        // network which would otherwise be separate.
+        let mut cut = HashSet::new();
+        for ring in &rings {
+            let steps: HashSet<_> = ring.windows(2).map(|w| (key(w[0]), key(w[1]))).collect();
+            cut.extend(steps.iter().filter(|&&(a, b)| a < b && steps.contains(&(b, a))).copied());
+        }
+        if cut.is_empty() {
+            return polys_from_rings(rings);
+        }
+        edges.retain(|&(a, b)| !cut.contains(&undirected(a, b)));
+    }
+}
+
+/// Polygons from rings which are not labelled as exteriors or holes.
+///
+/// Rings inside an odd number of other rings are holes of the smallest ring
+/// containing them, and the rest are exteriors, so islands inside holes
+/// become polygons of their own. Rings are expected not to cross each other.
+/// Rings with no area are dropped. Exteriors are wound counter-clockwise and
+/// holes clockwise.
+pub fn assemble_polys(rings: Vec<Ring>) -> Vec<Poly> {
+    let mut rings: Vec<Ring> = rings.into_iter().filter(|ring| ring.area() > 0.).collect();
+    rings.sort_by(|a, b| b.area().total_cmp(&a.area()));
+    let geoms: Vec<Geom> = rings.iter().map(Ring::geom).collect();
+    let mut polys: Vec<(Ring, Vec<Ring>)> = Vec::new();
+    // For each ring, whether it is a hole, and the index of its polygon.
+    let mut roles: Vec<(bool, usize)> = Vec::with_capacity(rings.len());
+    for (i, ring) in rings.iter().enumerate() {
+        match smallest_container(ring, &geoms[i], &rings[..i], &geoms[..i]) {
+            Some(j) if !roles[j].0 => {
+                let poly = roles[j].1;
+                polys[poly].1.push(ring.to_clockwise());
+                roles.push((true, poly));
+            }
+            _ => {
+                roles.push((false, polys.len()));
+                polys.push((ring.to_counter_clockwise(), Vec::new()));
+            }
+        }
+    }
+    polys
+        .iter()
+        .map(|(exterior, holes)| Poly::new(exterior, holes))
+        .collect()
+}
+
+/// The index of the smallest of `candidates` which covers `ring` and has a
+/// larger area. `geom` and `candidate_geoms` are the same rings as polygon
+/// geometries, built once by the caller rather than for every comparison.
+fn smallest_container(
+    ring: &Ring,
+    geom: &Geom,
+    candidates: &[Ring],
+    candidate_geoms: &[Geom],
+) -> Option<usize> {
+    candidates
+        .iter()
+        .zip(candidate_geoms)
+        .enumerate()
+        .filter(|(_, (candidate, _))| candidate.area() > ring.area() && candidate.rect().contains_rect(ring.rect()))
+        .filter(|(_, (_, candidate))| candidate.covers(geom))
+        .min_by(|(_, (x, _)), (_, (y, _))| x.area().total_cmp(&y.area()))
+        .map(|(i, _)| i)
+}
+
+/// The point as a hashable key. Adding zero turns `-0.` into `0.`, which
+/// would otherwise have different bits.
+fn key(p: Point) -> (u64, u64) {
+    ((p.x + 0.).to_bits(), (p.y + 0.).to_bits())
+}
+
+/// The keys of an edge's endpoints, in the same order either way round.
+fn undirected(a: Point, b: Point) -> ((u64, u64), (u64, u64)) {
+    let (a, b) = (key(a), key(b));
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// The distinct edges between the points where the lines meet.
+fn node(lines: &[Line]) -> Vec<(Point, Point)> {
+    let offsets: Vec<usize> = lines
+        .iter()
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.num_segments();
+            Some(start)
+        })
+        .collect();
+    let segments: Vec<Segment> = lines
+        .iter()
+        .flat_map(|line| (0..line.num_segments()).filter_map(|i| line.segment(i)))
+        .collect();
+    let mut splits: Vec<Vec<(f64, Point)>> = segments.iter().map(|s| vec![(0., s.a()), (1., s.b())]).collect();
+    let mut split = |index: usize, point: Point| {
+        let fraction = fraction(segments[index], point).clamp(0., 1.);
+        splits[index].push((fraction, point));
+    };
+    for (i, line) in lines.iter().enumerate() {
+        for (j, other) in lines.iter().enumerate().skip(i) {
+            if !line.rect().intersects_rect(other.rect()) {
+                continue;
+            }
+            let mut meets = Vec::new();
+            line.line_search(other, &mut |s: Segment, si: usize, t: Segment, ti: usize| {
+                if (i, si) != (j, ti) && s.intersects_segment(t) {
+                    meets.push((offsets[i] + si, s, offsets[j] + ti, t));
+                }
+                true
+            });
+            for (si, s, ti, t) in meets {
+                if cross(s.a(), s.b(), t.a()) == 0. && cross(s.a(), s.b(), t.b()) == 0. {
+                    for p in [t.a(), t.b()].into_iter().filter(|&p| s.contains_point(p)) {
+                        split(si, p);
+                    }
+                    for p in [s.a(), s.b()].into_iter().filter(|&p| t.contains_point(p)) {
+                        split(ti, p);
+                    }
+                    continue;
+                }
+                // Prefer exact vertices over computed intersections, so both
+                // sides are split at the same point.
+                let point = [t.a(), t.b()]
+                    .into_iter()
+                    .find(|&p| s.contains_point(p))
+                    .or_else(|| [s.a(), s.b()].into_iter().find(|&p| t.contains_point(p)))
+                    .or_else(|| s.intersection(t));
+                if let Some(point) = point {
+                    split(si, point);
+                    split(ti, point);
+                }
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    let mut edges = Vec::new();
+    for mut splits in splits {
+        splits.sort_by(|x, y| x.0.total_cmp(&y.0));
+        splits.dedup_by(|x, y| x.1 == y.1);
+        for pair in splits.windows(2) {
+            let (a, b) = (pair[0].1, pair[1].1);
+            if seen.insert(undirected(a, b)) {
+                edges.push((a, b));
+            }
+        }
+    }
+    edges
+}
+
+/// Remove edges with an end which no other edge meets, repeatedly.
+fn remove_dangles(edges: &mut Vec<(Point, Point)>) {
+    let mut degree: HashMap<(u64, u64), usize> = HashMap::new();
+    for &(a, b) in edges.iter() {
+        *degree.entry(key(a)).or_default() += 1;
+        *degree.entry(key(b)).or_default() += 1;
+    }
+    loop {
+        let before = edges.len();
+        edges.retain(|&(a, b)| {
+            let keep = degree[&key(a)] > 1 && degree[&key(b)] > 1;
+            if !keep {
+                for p in [a, b] {
+                    *degree.get_mut(&key(p)).expect("every endpoint has a degree") -= 1;
+                }
+            }
+            keep
+        });
+        if edges.len() == before {
+            return;
+        }
+    }
+}
+
+/// Link directed edges into closed rings, keeping the interior on the left.
+///
+/// Where several edges leave a point, the ring turns as far left as it can,
+/// so areas which only touch at a point end up in separate rings.
+pub(crate) fn link(edges: &[(Point, Point)]) -> Vec<Vec<Point>> {
+    let mut outgoing: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, &(a, _)) in edges.iter().enumerate() {
+        outgoing.entry(key(a)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = vec![edges[start].0];
+        let mut current = start;
+        used[start] = true;
+        let closed = loop {
+            let (a, b) = edges[current];
+            let back = (a.y - b.y).atan2(a.x - b.x);
+            // The clockwise angle from the way back to each way out.
+            let turn = |&i: &usize| {
+                let (_, c) = edges[i];
+                let angle = (back - (c.y - b.y).atan2(c.x - b.x)).rem_euclid(TAU);
+                if angle == 0. {
+                    TAU
+                } else {
+                    angle
+                }
+            };
+            let next = outgoing
+                .get(&key(b))
+                .into_iter()
+                .flatten()
+                .filter(|&&i| !used[i] || i == start)
+                .min_by(|x, y| turn(x).total_cmp(&turn(y)));
+            match next {
+                Some(&next) if next == start => break true,
+                Some(&next) => {
+                    ring.push(b);
+                    used[next] = true;
+                    current = next;
+                }
+                None => break false,
+            }
+        };
+        if closed {
+            ring.push(ring[0]);
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+/// Polygons from counter-clockwise exteriors and the clockwise holes inside
+/// them. Vertices in the middle of straight runs are dropped.
+pub(crate) fn polys_from_rings(rings: Vec<Vec<Point>>) -> Vec<Poly> {
+    let mut exteriors = Vec::new();
+    let mut holes = Vec::new();
+    for mut points in rings {
+        // drop vertices where the ring runs straight on
+        points.pop();
+        let n = points.len();
+        let straight: Vec<bool> = (0..n)
+            .map(|i| {
+                let (prev, p, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+                cross(prev, p, next) == 0. && (p.x - prev.x) * (next.x - p.x) + (p.y - prev.y) * (next.y - p.y) > 0.
+            })
+            .collect();
+        let mut points: Vec<Point> = points.into_iter().zip(straight).filter(|&(_, s)| !s).map(|(p, _)| p).collect();
+        if points.len() < 3 {
+            continue;
+        }
+        points.push(points[0]);
+        let ring = Ring::new(&points);
+        if ring.area() == 0. {
+            continue;
+        }
+        if ring.clockwise() {
+            holes.push(ring);
+        } else {
+            exteriors.push(ring);
+        }
+    }
+
+    let mut assigned: Vec<Vec<Ring>> = exteriors.iter().map(|_| Vec::new()).collect();
+    let geoms: Vec<Geom> = exteriors.iter().map(Ring::geom).collect();
+    for hole in holes {
+        if let Some(i) = smallest_container(&hole, &hole.geom(), &exteriors, &geoms) {
+            assigned[i].push(hole);
+        }
+    }
+    exteriors
+        .iter()
+        .zip(assigned)
+        .map(|(exterior, holes)| Poly::new(exterior, &holes))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assemble_polys, polygonize};
+    use crate::test_util::square;
+    use crate::{Line, Point};
+
+    fn line(points: &[(f64, f64)]) -> Line {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Line::new(&points)
+    }
+
+    fn areas(polys: &[crate::Poly]) -> Vec<f64> {
+        let mut areas: Vec<f64> = polys
+            .iter()
+            .map(|p| p.exterior().area() - p.holes().iter().map(|h| h.area()).sum::<f64>())
+            .collect();
+        areas.sort_by(f64::total_cmp);
+        areas
+    }
+
+    #[test]
+    fn crossing_lines() {
+        // A square cut in two by a line which overshoots it at both ends,
+        // with a dangling spur inside.
+        let lines = [
+            line(&[(0., 0.), (4., 0.), (4., 4.)]),
+            line(&[(4., 4.), (0., 4.), (0., 0.)]),
+            line(&[(1., -1.), (1., 5.)]),
+            line(&[(2., 2.), (3., 2.)]),
+            line(&[(1., 2.), (2., 2.)]),
+        ];
+        let polys = polygonize(&lines);
+        assert_eq!(areas(&polys), [4., 12.]);
+        assert!(polys.iter().all(|p| !p.exterior().clockwise() && p.num_holes() == 0));
+    }
+
+    #[test]
+    fn signed_zero() {
+        // The two lines meet where one has -0. and the other 0.
+        let lines = [
+            line(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.)]),
+            line(&[(-0., 4.), (-0., 2.), (0., -0.)]),
+        ];
+        assert_eq!(areas(&polygonize(&lines)), [16.]);
+    }
+
+    #[test]
+    fn nested_networks() {
+        let outer = square(0., 0., 10.);
+        let inner = square(4., 4., 2.).reversed();
+        let lines = [Line::new(outer.points()), Line::new(inner.points())];
+        let polys = polygonize(&lines);
+        assert_eq!(areas(&polys), [4., 96.]);
+        let holed = polys.iter().find(|p| p.num_holes() == 1).expect("the outer square has a hole");
+        assert!(holed.holes()[0].clockwise());
+    }
+
+    #[test]
+    fn cut_edges() {
+        // Two squares joined by a bridge, which bounds nothing.
+        let left = square(0., 0., 2.);
+        let right = square(5., 0., 2.);
+        let lines = [
+            Line::new(left.points()),
+            Line::new(right.points()),
+            line(&[(2., 1.), (5., 1.)]),
+        ];
+        assert_eq!(areas(&polygonize(&lines)), [4., 4.]);
+        assert!(polygonize(&[line(&[(0., 0.), (1., 1.)])]).is_empty());
+    }
+
+    #[test]
+    fn assemble() {
+        let rings = vec![
+            square(3., 3., 2.),
+            square(0., 0., 8.).reversed(),
+            square(20., 0., 1.).reversed(),
+            square(2., 2., 4.),
+            square(0., 0., 0.),
+        ];
+        let polys = assemble_polys(rings);
+        assert_eq!(polys.len(), 3);
+        assert_eq!(areas(&polys), [1., 4., 48.]);
+        assert!(polys.iter().all(|p| !p.exterior().clockwise()));
+        let holed = polys.iter().find(|p| p.num_holes() == 1).expect("the big square has a hole");
+        assert_eq!(holed.exterior().area(), 64.);
+        assert!(holed.holes()[0].clockwise());
+        assert!(holed.validate().is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Conversions between lon/lat coordinates (EPSG:4326) and spherical Web
+//! Mercator (EPSG:3857).
+//!
+//! Points are treated as `x = longitude, y = latitude` in degrees on the
+//! lon/lat side, and as meters on the Web Mercator side. Latitudes are
+//! clamped to [`MAX_LATITUDE`] before projecting, since the poles lie at
+//! infinity in Web Mercator.
+
+use std::f64::consts::PI;
+
+use crate::{Geom, Line, Point, Poly, Rect, Ring};
+
+/// The radius of the sphere used by Web Mercator, in meters.
+pub const EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// The largest latitude representable in Web Mercator, `atan(sinh(π))` in
+/// degrees. This is the latitude at which the projected world is square.
+pub const MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// Half the width (and height) of the projected world, in meters.
+pub const MAX_EXTENT: f64 = PI * EARTH_RADIUS;
+
+/// Project a lon/lat point in degrees to Web Mercator meters.
+///
+/// The latitude is clamped to ±[`MAX_LATITUDE`]. The longitude is not
+/// wrapped.
+pub fn lonlat_to_web_mercator(point: Point) -> Point {
+    let lat = point.y.clamp(-MAX_LATITUDE, MAX_LATITUDE);
+    Point::new(
+        EARTH_RADIUS * point.x.to_radians(),
+        EARTH_RADIUS * (PI / 4. + lat.to_radians() / 2.).tan().ln(),
+    )
+}
+
+/// Unproject a Web Mercator point in meters to lon/lat degrees.
+///
+/// The y coordinate is clamped to ±[`MAX_EXTENT`], so the resulting latitude
+/// never exceeds ±[`MAX_LATITUDE`].
+pub fn web_mercator_to_lonlat(point: Point) -> Point {
+    let y = point.y.clamp(-MAX_EXTENT, MAX_EXTENT);
+    Point::new(
+        (point.x / EARTH_RADIUS).to_degrees(),
+        (y / EARTH_RADIUS).sinh().atan().to_degrees(),
+    )
+}
+
+/// Web Mercator projection
+impl Point {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    pub fn to_web_mercator(self) -> Point {
+        lonlat_to_web_mercator(self)
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(self) -> Point {
+        web_mercator_to_lonlat(self)
+    }
+}
+
+/// Web Mercator projection
+impl Rect {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    ///
+    /// Both projections are monotonic along each axis, so projecting the
+    /// corners yields the bounding rect of the projected area.
+    pub fn to_web_mercator(self) -> Rect {
+        Rect::new(self.min().to_web_mercator(), self.max().to_web_mercator())
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(self) -> Rect {
+        Rect::new(self.min().to_lonlat(), self.max().to_lonlat())
+    }
+}
+
+/// Web Mercator projection
+impl Line {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    pub fn to_web_mercator(&self) -> Line {
+        self.map_points(lonlat_to_web_mercator)
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(&self) -> Line {
+        self.map_points(web_mercator_to_lonlat)
+    }
+}
+
+/// Web Mercator projection
+impl Ring {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    pub fn to_web_mercator(&self) -> Ring {
+        self.map_points(lonlat_to_web_mercator)
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(&self) -> Ring {
+        self.map_points(web_mercator_to_lonlat)
+    }
+}
+
+/// Web Mercator projection
+impl Poly {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    pub fn to_web_mercator(&self) -> Poly {
+        self.map_points(lonlat_to_web_mercator)
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(&self) -> Poly {
+        self.map_points(web_mercator_to_lonlat)
+    }
+}
+
+/// Web Mercator projection
+impl Geom {
+    /// Project from lon/lat degrees to Web Mercator meters.
+    pub fn to_web_mercator(&self) -> Geom {
+        self.map_points(lonlat_to_web_mercator)
+    }
+
+    /// Unproject from Web Mercator meters to lon/lat degrees.
+    pub fn to_lonlat(&self) -> Geom {
+        self.map_points(web_mercator_to_lonlat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_EXTENT, MAX_LATITUDE};
+    use crate::{Geom, GeomType, Line, Point, Rect};
+
+    fn assert_close(a: Point, b: Point, eps: f64) {
+        assert!(
+            (a.x - b.x).abs() < eps && (a.y - b.y).abs() < eps,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn known_values() {
+        assert_close(Point::new(0., 0.).to_web_mercator(), Point::new(0., 0.), 1e-9);
+        assert_close(
+            Point::new(180., MAX_LATITUDE).to_web_mercator(),
+            Point::new(MAX_EXTENT, MAX_EXTENT),
+            1e-6,
+        );
+        assert_close(
+            Point::new(-180., -MAX_LATITUDE).to_web_mercator(),
+            Point::new(-MAX_EXTENT, -MAX_EXTENT),
+            1e-6,
+        );
+    }
+
+    #[test]
+    fn latitude_is_clamped() {
+        let pole = Point::new(10., 90.).to_web_mercator();
+        assert!(pole.y.is_finite());
+        assert_close(pole, Point::new(10., MAX_LATITUDE).to_web_mercator(), 1e-9);
+        let beyond = Point::new(0., 2. * MAX_EXTENT).to_lonlat();
+        assert_close(beyond, Point::new(0., MAX_LATITUDE), 1e-9);
+    }
+
+    #[test]
+    fn round_trip() {
+        let pt = Point::new(-122.4194, 37.7749);
+        assert_close(pt.to_web_mercator().to_lonlat(), pt, 1e-9);
+        let rect = Rect::new(Point::new(-10., -20.), Point::new(30., 40.));
+        let back = rect.to_web_mercator().to_lonlat();
+        assert_close(back.min(), rect.min(), 1e-9);
+        assert_close(back.max(), rect.max(), 1e-9);
+    }
+
+    #[test]
+    fn geometries() {
+        let line = Line::new(&[Point::new(0., 0.), Point::new(10., 10.)]);
+        let projected = line.geom().to_web_mercator();
+        assert_eq!(projected.geom_type(), GeomType::LineString);
+        let points = projected.line().unwrap().points().to_vec();
+        assert_close(points[1], Point::new(10., 10.).to_web_mercator(), 1e-9);
+
+        let multi = Geom::new_multipoint(&[Point::new(1., 2.), Point::new(3., 4.)]);
+        let back = multi.to_web_mercator().to_lonlat();
+        assert_eq!(back.geom_type(), GeomType::MultiPoint);
+        assert_close(back.point_at(1).unwrap(), Point::new(3., 4.), 1e-9);
+
+        let z = Point::new(5., 5.).geom_with_z(12.);
+        let projected = z.to_web_mercator();
+        assert!(projected.has_z());
+        assert_eq!(projected.z(), 12.);
+    }
+}
@@ -11,7 +11,7 @@ use core::fmt;
 
 use tg_sys::{tg_rect, RectFuncs};
 
-use crate::Point;
+use crate::{Geom, Point, Poly, Ring};
 
 #[repr(transparent)]
 #[derive(Clone, Copy)]
@@ -85,6 +85,45 @@ impl Rect {
     }
 }
 
+/// Conversions
+impl Rect {
+    /// The rect as a polygon geometry.
+    pub(crate) fn geom(self) -> Geom {
+        let (min, max) = (self.min(), self.max());
+        let ring = Ring::new(&[min, Point::new(max.x, min.y), max, Point::new(min.x, max.y), min]);
+        Poly::from(ring).geom()
+    }
+}
+
+/// Geometric measurements
+impl Rect {
+    /// Whether `other` lies entirely inside the rect, including its boundary.
+    pub fn contains_rect(self, other: Rect) -> bool {
+        let (min, max) = (self.min(), self.max());
+        let (other_min, other_max) = (other.min(), other.max());
+        min.x <= other_min.x && min.y <= other_min.y && max.x >= other_max.x && max.y >= other_max.y
+    }
+
+    /// The euclidean distance from `point` to the rect, which is zero for
+    /// points inside it.
+    pub fn distance_to_point(self, point: Point) -> f64 {
+        let (min, max) = (self.min(), self.max());
+        let dx = (min.x - point.x).max(point.x - max.x).max(0.);
+        let dy = (min.y - point.y).max(point.y - max.y).max(0.);
+        dx.hypot(dy)
+    }
+
+    /// The euclidean distance between the closest points of the two rects,
+    /// which is zero if they intersect.
+    pub fn distance_to_rect(self, other: Rect) -> f64 {
+        let (min, max) = (self.min(), self.max());
+        let (other_min, other_max) = (other.min(), other.max());
+        let dx = (min.x - other_max.x).max(other_min.x - max.x).max(0.);
+        let dy = (min.y - other_max.y).max(other_min.y - max.y).max(0.);
+        dx.hypot(dy)
+    }
+}
+
 impl fmt::Debug for Rect {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Rect")
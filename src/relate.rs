@@ -0,0 +1,618 @@
+//! The [DE-9IM] intersection matrix between two geometries.
+//!
+//! Each geometry is broken into its points, lines and polygons. The segments
+//! of each line and polygon ring are split wherever the other geometry's
+//! segments meet them, with candidate pairs found by tg's indexed segment
+//! searches. Every piece then lies entirely in the interior, boundary or
+//! exterior of the other geometry, which its midpoint tells. The pieces,
+//! every vertex and every meeting point together fill in the matrix.
+//!
+//! Lines follow the mod-2 rule: their boundary is the endpoints shared by an
+//! odd number of lines, so closed lines have none. Collections are treated
+//! as the union of their parts, with polygons taking precedence over lines
+//! and lines over points where they overlap.
+//!
+//! [DE-9IM]: https://en.wikipedia.org/wiki/DE-9IM
+
+use core::fmt;
+
+use crate::{point::cross, Geom, GeomType, Line, Point, Poly, Rect, Ring, Segment};
+
+/// Where a point lies relative to a geometry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Location {
+    Interior,
+    Boundary,
+    Exterior,
+}
+
+/// The dimensions of the intersections between the interior, boundary and
+/// exterior of one geometry and those of another.
+///
+/// Displays as the usual nine character string, row by row, with `F` for
+/// empty intersections.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntersectionMatrix {
+    cells: [[Option<u8>; 3]; 3],
+}
+
+impl IntersectionMatrix {
+    /// The dimension of the intersection of location `a` of the first
+    /// geometry with location `b` of the second, or `None` if they don't
+    /// intersect.
+    pub fn get(&self, a: Location, b: Location) -> Option<u8> {
+        self.cells[a as usize][b as usize]
+    }
+
+    /// The matrix with the geometries swapped.
+    pub fn transpose(&self) -> IntersectionMatrix {
+        let mut cells = [[None; 3]; 3];
+        for (i, row) in self.cells.iter().enumerate() {
+            for (j, &cell) in row.iter().enumerate() {
+                cells[j][i] = cell;
+            }
+        }
+        IntersectionMatrix { cells }
+    }
+
+    /// Whether the matrix matches a nine character pattern of `T` (any
+    /// intersection), `F` (no intersection), `0`, `1`, `2` (an intersection
+    /// of that dimension) and `*` (anything).
+    ///
+    /// Returns an error if the pattern is not nine of those characters.
+    pub fn matches(&self, pattern: &str) -> Result<bool, PatternError> {
+        let length = pattern.chars().count();
+        if length != 9 {
+            return Err(PatternError::WrongLength(length));
+        }
+        let mut matched = true;
+        for (c, &cell) in pattern.chars().zip(self.cells.iter().flatten()) {
+            matched &= match c.to_ascii_uppercase() {
+                'T' => cell.is_some(),
+                'F' => cell.is_none(),
+                '*' => true,
+                '0'..='2' => cell == Some(c as u8 - b'0'),
+                _ => return Err(PatternError::InvalidCharacter(c)),
+            };
+        }
+        Ok(matched)
+    }
+
+    /// [`IntersectionMatrix::matches`], for the patterns written out in this
+    /// module.
+    fn fits(&self, pattern: &str) -> bool {
+        self.matches(pattern).expect("built-in DE-9IM pattern is valid")
+    }
+
+    /// Raise the cell for `a` and `b` to at least `dimension`.
+    fn raise(&mut self, a: Location, b: Location, dimension: u8) {
+        let cell = &mut self.cells[a as usize][b as usize];
+        *cell = (*cell).max(Some(dimension));
+    }
+
+    fn union(mut self, other: IntersectionMatrix) -> IntersectionMatrix {
+        for (row, other) in self.cells.iter_mut().zip(other.cells) {
+            for (cell, other) in row.iter_mut().zip(other) {
+                *cell = (*cell).max(other);
+            }
+        }
+        self
+    }
+}
+
+/// Why a string is not a DE-9IM pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// Patterns are nine characters long, not this many.
+    WrongLength(usize),
+    /// A character other than `T`, `F`, `*`, `0`, `1` or `2`.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::WrongLength(length) => write!(f, "DE-9IM pattern has {length} characters, not nine"),
+            PatternError::InvalidCharacter(c) => write!(f, "invalid character {c:?} in DE-9IM pattern"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl fmt::Display for IntersectionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for cell in self.cells.iter().flatten() {
+            match cell {
+                Some(dimension) => write!(f, "{dimension}")?,
+                None => f.write_str("F")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// DE-9IM
+impl Geom {
+    /// The DE-9IM intersection matrix of `self` and `other`.
+    pub fn relate(&self, other: &Geom) -> IntersectionMatrix {
+        let (a, b) = (Parts::new(self), Parts::new(other));
+        let mut matrix = IntersectionMatrix { cells: [[None; 3]; 3] };
+        matrix.raise(Location::Exterior, Location::Exterior, 2);
+        // Lines and points can't cover any area.
+        if !a.polys.is_empty() && b.polys.is_empty() {
+            matrix.raise(Location::Interior, Location::Exterior, 2);
+        }
+        if a.polys.is_empty() && !b.polys.is_empty() {
+            matrix.raise(Location::Exterior, Location::Interior, 2);
+        }
+        matrix.union(a.relate(&b)).union(b.relate(&a).transpose())
+    }
+
+    /// Whether the DE-9IM intersection matrix of `self` and `other` matches
+    /// `pattern`, as in [`IntersectionMatrix::matches`].
+    pub fn relate_pattern(&self, other: &Geom, pattern: &str) -> Result<bool, PatternError> {
+        self.relate(other).matches(pattern)
+    }
+
+    /// Whether the geometries share some interior points, but neither's
+    /// interior is a subset of the other's, and the shared part has a lower
+    /// dimension than the larger of the two.
+    ///
+    /// Two polygons or two multipoints never cross.
+    pub fn crosses(&self, other: &Geom) -> bool {
+        let (Some(a), Some(b)) = (dimension(self), dimension(other)) else {
+            return false;
+        };
+        let matrix = self.relate(other);
+        match (a, b) {
+            (1, 1) => matrix.fits("0********"),
+            _ if a < b => matrix.fits("T*T******"),
+            _ if a > b => matrix.fits("T*****T**"),
+            _ => false,
+        }
+    }
+
+    /// Whether the geometries have the same dimension, share some interior
+    /// points of that dimension, and neither covers the other.
+    pub fn overlaps(&self, other: &Geom) -> bool {
+        let (Some(a), Some(b)) = (dimension(self), dimension(other)) else {
+            return false;
+        };
+        let matrix = self.relate(other);
+        match (a, b) {
+            (1, 1) => matrix.fits("1*T***T**"),
+            _ if a == b => matrix.fits("T*T***T**"),
+            _ => false,
+        }
+    }
+}
+
+/// The highest dimension of the geometry's parts, or `None` if it is empty.
+fn dimension(geom: &Geom) -> Option<u8> {
+    let parts = Parts::new(geom);
+    if !parts.polys.is_empty() {
+        Some(2)
+    } else if !parts.lines.is_empty() {
+        Some(1)
+    } else if !parts.points.is_empty() {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+/// A line, or a polygon ring, whose segments get split where they meet the
+/// other geometry.
+enum Edges {
+    Line(Line),
+    /// A ring, and whether the interior of its polygon is on the left.
+    Ring(Ring, bool),
+}
+
+impl Edges {
+    fn rect(&self) -> Rect {
+        match self {
+            Edges::Line(line) => line.rect(),
+            Edges::Ring(ring, _) => ring.rect(),
+        }
+    }
+
+    fn num_segments(&self) -> usize {
+        match self {
+            Edges::Line(line) => line.num_segments(),
+            Edges::Ring(ring, _) => ring.num_segments(),
+        }
+    }
+
+    fn segment(&self, index: usize) -> Option<Segment> {
+        match self {
+            Edges::Line(line) => line.segment(index),
+            Edges::Ring(ring, _) => ring.segment(index),
+        }
+    }
+
+    /// Where the points of the edges lie in their own geometry, apart from
+    /// line endpoints.
+    fn location(&self) -> Location {
+        match self {
+            Edges::Line(_) => Location::Interior,
+            Edges::Ring(..) => Location::Boundary,
+        }
+    }
+
+    /// Visit the pairs of segments from `self` and `other` whose rects
+    /// intersect, through the segment indexes.
+    fn search(&self, other: &Edges, mut visit: impl FnMut(Segment, usize, Segment, usize) -> bool) {
+        match (self, other) {
+            (Edges::Line(a), Edges::Line(b)) => a.line_search(b, &mut visit),
+            (Edges::Ring(a, _), Edges::Line(b)) => a.line_search(b, &mut visit),
+            (Edges::Ring(a, _), Edges::Ring(b, _)) => a.ring_search(b, &mut visit),
+            (Edges::Line(a), Edges::Ring(b, _)) => {
+                b.line_search(a, &mut |s: Segment, i: usize, t: Segment, j: usize| visit(t, j, s, i))
+            }
+        }
+    }
+}
+
+/// Where a segment of one geometry runs along a segment of the other,
+/// between two fractions of its length.
+struct Overlap {
+    start: f64,
+    end: f64,
+    /// Whether the other geometry's interior is on the left of the segment,
+    /// if the other segment is on a polygon ring.
+    interior_left: Option<bool>,
+}
+
+/// A geometry broken into its parts.
+struct Parts {
+    points: Vec<Point>,
+    lines: Vec<Line>,
+    polys: Vec<Poly>,
+    /// The endpoints of an odd number of lines.
+    boundary: Vec<Point>,
+    edges: Vec<Edges>,
+}
+
+impl Parts {
+    fn new(geom: &Geom) -> Parts {
+        let mut parts = Parts {
+            points: Vec::new(),
+            lines: Vec::new(),
+            polys: Vec::new(),
+            boundary: Vec::new(),
+            edges: Vec::new(),
+        };
+        parts.add(geom);
+
+        let mut ends: Vec<Point> = parts
+            .lines
+            .iter()
+            .flat_map(|line| [line.points()[0], line.points()[line.num_points() - 1]])
+            // Adding zero turns -0. into 0., so that they sort together.
+            .map(|p| Point::new(p.x + 0., p.y + 0.))
+            .collect();
+        ends.sort_by(|p, q| p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y)));
+        for group in ends.chunk_by(|p, q| p == q) {
+            if group.len() % 2 == 1 {
+                parts.boundary.push(group[0]);
+            }
+        }
+
+        parts.edges.extend(parts.lines.iter().map(|line| Edges::Line(line.clone())));
+        for poly in &parts.polys {
+            let exterior = poly.exterior();
+            let left = !exterior.clockwise();
+            parts.edges.push(Edges::Ring(exterior, left));
+            for hole in poly.holes() {
+                let left = hole.clockwise();
+                parts.edges.push(Edges::Ring(hole, left));
+            }
+        }
+        parts
+    }
+
+    fn add(&mut self, geom: &Geom) {
+        if geom.is_empty() {
+            return;
+        }
+        match geom.geom_type() {
+            GeomType::Point => self.points.push(geom.point()),
+            GeomType::MultiPoint => self.points.extend((0..geom.num_points()).filter_map(|i| geom.point_at(i))),
+            GeomType::LineString => self.add_line(geom.line()),
+            GeomType::MultiLineString => {
+                for i in 0..geom.num_lines() {
+                    self.add_line(geom.line_at(i));
+                }
+            }
+            GeomType::Polygon => self.polys.extend(geom.poly()),
+            GeomType::MultiPolygon => self.polys.extend((0..geom.num_polys()).filter_map(|i| geom.poly_at(i))),
+            GeomType::GeometryCollection => {
+                for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                    self.add(&child);
+                }
+            }
+        }
+    }
+
+    fn add_line(&mut self, line: Option<Line>) {
+        match line {
+            Some(line) if line.num_segments() > 0 => self.lines.push(line),
+            Some(line) => self.points.extend_from_slice(line.points()),
+            None => {}
+        }
+    }
+
+    fn vertices(&self) -> impl Iterator<Item = Point> + '_ {
+        let lines = self.lines.iter().flat_map(|line| line.points().iter().copied());
+        let rings = self.edges.iter().flat_map(|edges| match edges {
+            Edges::Ring(ring, _) => ring.points(),
+            Edges::Line(_) => &[],
+        });
+        self.points.iter().copied().chain(lines).chain(rings.copied())
+    }
+
+    /// Where `point` lies relative to the geometry.
+    fn locate(&self, point: Point) -> Location {
+        let mut on_boundary = false;
+        for poly in self.polys.iter().filter(|poly| poly.rect().intersects_point(point)) {
+            let mut rings = std::iter::once(poly.exterior()).chain(poly.holes());
+            if rings.any(|ring| on_ring(&ring, point)) {
+                on_boundary = true;
+            } else if poly.intersects_point(point) {
+                return Location::Interior;
+            }
+        }
+        if on_boundary || self.boundary.contains(&point) {
+            Location::Boundary
+        } else if self.lines.iter().any(|line| on_line(line, point)) || self.points.contains(&point) {
+            Location::Interior
+        } else {
+            Location::Exterior
+        }
+    }
+
+    /// Whether `point` is inside or on one of the polygons.
+    fn in_area(&self, point: Point) -> bool {
+        self.polys.iter().any(|poly| poly.intersects_point(point))
+    }
+
+    /// Where the points on `edges` lie in this geometry, at `point`.
+    fn edge_location(&self, edges: &Edges, point: Point) -> Location {
+        match edges {
+            Edges::Line(_) if self.boundary.contains(&point) => Location::Boundary,
+            _ => edges.location(),
+        }
+    }
+
+    /// The parts of the matrix seen from this geometry: where its vertices,
+    /// the pieces of its edges, and the areas on either side of its rings
+    /// lie in `other`.
+    fn relate(&self, other: &Parts) -> IntersectionMatrix {
+        let mut matrix = IntersectionMatrix { cells: [[None; 3]; 3] };
+        for vertex in self.vertices() {
+            matrix.raise(self.locate(vertex), other.locate(vertex), 0);
+        }
+        for edges in &self.edges {
+            let n = edges.num_segments();
+            let mut splits = vec![vec![0., 1.]; n];
+            let mut overlaps: Vec<Vec<Overlap>> = (0..n).map(|_| Vec::new()).collect();
+            for other_edges in other.edges.iter().filter(|e| e.rect().intersects_rect(edges.rect())) {
+                edges.search(other_edges, |s, i, t, _| {
+                    if !s.intersects_segment(t) {
+                        return true;
+                    }
+                    let (a, b) = (s.a(), s.b());
+                    let (dx, dy) = (b.x - a.x, b.y - a.y);
+                    let len2 = dx * dx + dy * dy;
+                    if len2 == 0. {
+                        return true;
+                    }
+                    let fraction = |p: Point| ((p.x - a.x) * dx + (p.y - a.y) * dy) / len2;
+                    if cross(a, b, t.a()) == 0. && cross(a, b, t.b()) == 0. {
+                        let (u, v) = (fraction(t.a()), fraction(t.b()));
+                        let (start, end) = (u.min(v).max(0.), u.max(v).min(1.));
+                        splits[i].extend([start, end]);
+                        if start < end {
+                            let interior_left = match other_edges {
+                                Edges::Ring(_, left) => {
+                                    let same = dx * (t.b().x - t.a().x) + dy * (t.b().y - t.a().y) > 0.;
+                                    Some(*left == same)
+                                }
+                                Edges::Line(_) => None,
+                            };
+                            overlaps[i].push(Overlap {
+                                start,
+                                end,
+                                interior_left,
+                            });
+                        }
+                        return true;
+                    }
+                    // Prefer exact vertices over computed intersections.
+                    let point = [t.a(), t.b()]
+                        .into_iter()
+                        .find(|&p| s.contains_point(p))
+                        .or_else(|| [a, b].into_iter().find(|&p| t.contains_point(p)))
+                        .or_else(|| s.intersection(t));
+                    if let Some(point) = point {
+                        splits[i].push(fraction(point).clamp(0., 1.));
+                        matrix.raise(
+                            self.edge_location(edges, point),
+                            other.edge_location(other_edges, point),
+                            0,
+                        );
+                    }
+                    true
+                });
+            }
+            for (i, (mut splits, overlaps)) in splits.into_iter().zip(overlaps).enumerate() {
+                let Some(segment) = edges.segment(i) else {
+                    continue;
+                };
+                splits.sort_by(f64::total_cmp);
+                splits.dedup();
+                for pair in splits.windows(2) {
+                    let (start, end) = (pair[0], pair[1]);
+                    let t = (start + end) / 2.;
+                    let (a, b) = (segment.a(), segment.b());
+                    let mid = Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y));
+                    let along = overlaps.iter().filter(|o| o.start <= start && end <= o.end);
+                    let along_ring = along.clone().find_map(|o| o.interior_left);
+                    let in_area = along_ring.is_none() && other.in_area(mid);
+                    let location = if along_ring.is_some() {
+                        Location::Boundary
+                    } else if in_area || along.clone().next().is_some() {
+                        Location::Interior
+                    } else {
+                        Location::Exterior
+                    };
+                    matrix.raise(edges.location(), location, 1);
+
+                    // Where the areas on either side of a ring lie in the
+                    // other geometry's area.
+                    let Edges::Ring(_, left) = edges else {
+                        continue;
+                    };
+                    let (inside, outside) = match along_ring {
+                        Some(other_left) if other_left == *left => (Location::Interior, Location::Exterior),
+                        Some(_) => (Location::Exterior, Location::Interior),
+                        None if in_area => (Location::Interior, Location::Interior),
+                        None => (Location::Exterior, Location::Exterior),
+                    };
+                    matrix.raise(Location::Interior, inside, 2);
+                    matrix.raise(Location::Exterior, outside, 2);
+                }
+            }
+        }
+        matrix
+    }
+}
+
+fn on_ring(ring: &Ring, point: Point) -> bool {
+    ring.nearest_segment_to_point(point)
+        .is_some_and(|(segment, _, _)| segment.contains_point(point))
+}
+
+fn on_line(line: &Line, point: Point) -> bool {
+    line.rect().intersects_point(point)
+        && line
+            .nearest_segment_to_point(point)
+            .is_some_and(|(segment, _, _)| segment.contains_point(point))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntersectionMatrix, Location, PatternError};
+    use crate::test_util::square;
+    use crate::{Geom, Line, Point, Poly, Ring};
+
+    fn line(points: &[(f64, f64)]) -> Geom {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Line::new(&points).geom()
+    }
+
+    fn relate(a: &Geom, b: &Geom) -> String {
+        let matrix = a.relate(b);
+        assert_eq!(b.relate(a), matrix.transpose());
+        matrix.to_string()
+    }
+
+    #[test]
+    fn polygons() {
+        assert_eq!(relate(&square(0., 0., 10.).geom(), &square(5., 5., 10.).geom()), "212101212");
+        // sharing an edge
+        assert_eq!(relate(&square(0., 0., 1.).geom(), &square(1., 0., 1.).geom()), "FF2F11212");
+        // sharing a corner
+        assert_eq!(relate(&square(0., 0., 1.).geom(), &square(1., 1., 1.).geom()), "FF2F01212");
+        assert_eq!(relate(&square(0., 0., 10.).geom(), &square(2., 2., 1.).geom()), "212FF1FF2");
+        assert_eq!(relate(&square(0., 0., 10.).geom(), &square(20., 0., 1.).geom()), "FF2FF1212");
+        // inside, touching the boundary from within
+        assert_eq!(relate(&square(0., 0., 10.).geom(), &square(0., 0., 5.).geom()), "212F11FF2");
+
+        // the same square starting from another corner
+        let shifted = Poly::from(Ring::new(&[
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+        ]))
+        .geom();
+        assert_eq!(relate(&square(0., 0., 10.).geom(), &shifted), "2FFF1FFF2");
+
+        // inside the hole, and filling it exactly
+        let holed = Poly::new(&square(0., 0., 10.), &[square(2., 2., 6.)]).geom();
+        assert_eq!(relate(&holed, &square(3., 3., 1.).geom()), "FF2FF1212");
+        assert_eq!(relate(&holed, &square(2., 2., 6.).geom()), "FF2F112F2");
+        assert_eq!(relate(&holed, &square(1., 1., 2.).geom()), "212101212");
+    }
+
+    #[test]
+    fn lines_and_points() {
+        let zone = square(0., 0., 10.).geom();
+        assert_eq!(relate(&line(&[(-5., 5.), (5., 5.)]), &zone), "1010F0212");
+        assert_eq!(relate(&line(&[(2., 2.), (5., 5.)]), &zone), "1FF0FF212");
+        // along the boundary
+        assert_eq!(relate(&line(&[(0., 0.), (10., 0.)]), &zone), "F1FF0F212");
+
+        let a = line(&[(0., 0.), (10., 10.)]);
+        assert_eq!(relate(&a, &line(&[(0., 10.), (10., 0.)])), "0F1FF0102");
+        assert_eq!(relate(&a, &line(&[(5., 5.), (20., 20.)])), "1010F0102");
+        // a closed line has no boundary
+        let closed = line(&[(0., 0.), (1., 0.), (1., 1.), (0., 0.)]);
+        assert_eq!(relate(&closed, &Point::new(0., 0.).geom()), "0F1FFFFF2");
+
+        assert_eq!(relate(&Point::new(5., 5.).geom(), &zone), "0FFFFF212");
+        assert_eq!(relate(&Point::new(0., 5.).geom(), &zone), "F0FFFF212");
+        assert_eq!(relate(&Point::new(0., 0.).geom(), &a), "F0FFFF102");
+        assert_eq!(relate(&Geom::new_multipoint(&[]), &zone), "FFFFFF212");
+    }
+
+    #[test]
+    fn signed_zero() {
+        // Two lines meet at the origin, written as 0. in one and -0. in the
+        // other, so it is interior to the network rather than boundary.
+        let network = |zero: f64| {
+            let line = |points: [(f64, f64); 2]| Line::new(&points.map(|(x, y)| Point::new(x, y)));
+            Geom::new_multilinestring(&[
+                line([(0., 0.), (0., 1.)]),
+                line([(zero, 0.), (5., 0.)]),
+                line([(zero, 3.), (zero, 4.)]),
+            ])
+        };
+        let origin = Point::new(0., 0.).geom();
+        for zero in [0., -0.] {
+            let matrix = origin.relate(&network(zero));
+            assert_eq!(matrix.get(Location::Interior, Location::Interior), Some(0));
+            assert_eq!(matrix.get(Location::Interior, Location::Boundary), None);
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        let zone = square(0., 0., 10.).geom();
+        let matrix = zone.relate(&square(5., 5., 10.).geom());
+        assert_eq!(matrix.get(Location::Boundary, Location::Boundary), Some(0));
+        assert_eq!(matrix.get(Location::Interior, Location::Exterior), Some(2));
+        assert_eq!(matrix.matches("T*T***T**"), Ok(true));
+        assert_eq!(matrix.matches("2t2**1***"), Ok(true));
+        assert_eq!(matrix.matches("T*F**F***"), Ok(false));
+        assert_eq!(zone.relate_pattern(&square(2., 2., 1.).geom(), "T*****FF*"), Ok(true));
+
+        assert!(zone.overlaps(&square(5., 5., 10.).geom()));
+        assert!(!zone.overlaps(&square(2., 2., 1.).geom()));
+        assert!(line(&[(-5., 5.), (5., 5.)]).crosses(&zone));
+        assert!(!line(&[(2., 2.), (5., 5.)]).crosses(&zone));
+        assert!(line(&[(0., 0.), (10., 10.)]).crosses(&line(&[(0., 10.), (10., 0.)])));
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        let matrix = IntersectionMatrix { cells: [[None; 3]; 3] };
+        assert_eq!(matrix.matches("T*F"), Err(PatternError::WrongLength(3)));
+        assert_eq!(matrix.matches("T*F**F**X"), Err(PatternError::InvalidCharacter('X')));
+        assert_eq!(matrix.matches("T*F**F**é"), Err(PatternError::InvalidCharacter('é')));
+    }
+}
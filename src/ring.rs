@@ -8,7 +8,7 @@
 //! - [x] Add Geom conversions
 //! - [x] Add RingFuncs
 //! - [ ] Standard traits
-//! - [ ] Decide if PartialEq should include chirality or origin
+//! - [x] Decide if PartialEq should include chirality or origin
 //! - [ ] Documentation
 
 use std::{
@@ -18,9 +18,12 @@ use std::{
     ptr::NonNull,
 };
 
-use tg_sys::{tg_point, tg_rect, tg_ring, tg_segment, RingFuncs};
+use tg_sys::{tg_point, tg_poly, tg_rect, tg_ring, tg_segment, GeometryConstructors, RingFuncs};
 
-use crate::{IndexType, SearchVisitor, NearestSegmentVisitor, Point, Rect, Segment, Line};
+use crate::{
+    error::check_points, visitors::NearestToPoint, ConstructionError, Geom, IndexType, Line,
+    NearestSegmentVisitor, Point, Rect, SearchVisitor, Segment,
+};
 
 pub struct Ring {
     inner: NonNull<tg_ring>,
@@ -48,6 +51,54 @@ impl Ring {
         unsafe { Ring::from_raw_unchecked(raw) }
     }
 
+    /// Create a ring, rejecting input which [`Ring::new`] would accept but
+    /// which does not make a usable ring.
+    ///
+    /// The ring needs at least 3 vertices besides the closing point, finite
+    /// coordinates, and no more than `i32::MAX` points. If `close` is true, an
+    /// open ring is closed by repeating its first point; otherwise it is an
+    /// error.
+    pub fn try_from_points(points: &[Point], close: bool) -> Result<Ring, ConstructionError> {
+        Ring::try_from_points_indexed(points, close, IndexType::Default)
+    }
+
+    pub fn try_from_points_indexed(
+        points: &[Point],
+        close: bool,
+        index: IndexType,
+    ) -> Result<Ring, ConstructionError> {
+        // Checked before closing so that NaNs aren't reported as an open ring.
+        let mut len = check_points(points)?;
+        let mut closed;
+        let points = match (points.first(), points.last()) {
+            (Some(&first), Some(&last)) if first != last => {
+                if !close {
+                    return Err(ConstructionError::UnclosedRing { first, last });
+                }
+                len = len.checked_add(1).ok_or(ConstructionError::TooManyPoints {
+                    count: points.len() + 1,
+                })?;
+                closed = points.to_vec();
+                closed.push(first);
+                &closed[..]
+            }
+            _ => points,
+        };
+        let vertices = points.len().saturating_sub(1);
+        if vertices < 3 {
+            return Err(ConstructionError::TooFewPoints {
+                count: vertices,
+                min: 3,
+            });
+        }
+        let ptr = points.as_ptr() as *const tg_point;
+        let raw = unsafe { RingFuncs::tg_ring_new_ix(ptr, len, index.into()) };
+        if raw.is_null() {
+            Ring::handle_alloc_error();
+        }
+        Ok(unsafe { Ring::from_raw_unchecked(raw) })
+    }
+
     pub fn as_raw(&self) -> *mut tg_ring {
         self.inner.as_ptr()
     }
@@ -75,6 +126,25 @@ impl Ring {
         unsafe { Ring::from_raw_unchecked(raw) }
     }
 
+    /// Take a new reference to a ring owned by another tg object, such as the
+    /// exterior of a polygon.
+    pub(crate) fn clone_from_raw(raw: *const tg_ring) -> Ring {
+        let new = unsafe { RingFuncs::tg_ring_clone(raw) };
+        if new.is_null() {
+            Ring::handle_alloc_error();
+        }
+        unsafe { Ring::from_raw_unchecked(new) }
+    }
+
+    /// Create a Polygon geometry with this ring as its exterior.
+    pub fn geom(&self) -> Geom {
+        let raw = unsafe { GeometryConstructors::tg_geom_new_polygon(self.as_raw() as *const tg_poly) };
+        if raw.is_null() {
+            Geom::handle_alloc_error();
+        }
+        unsafe { Geom::from_raw_unchecked(raw) }
+    }
+
     fn handle_alloc_error() -> ! {
         let layout = Layout::new::<tg_ring>();
         handle_alloc_error(layout)
@@ -102,6 +172,12 @@ impl Ring {
         }
     }
 
+    /// The distinct vertices of the ring, without the closing point if the
+    /// ring was created with one.
+    pub(crate) fn vertices(&self) -> &[Point] {
+        &self.points()[..self.num_segments()]
+    }
+
     /// Get the point at the given index.
     pub fn point(&self, index: usize) -> Option<Point> {
         (index < self.num_points()).then(|| self.point_unchecked(index))
@@ -244,6 +320,14 @@ impl Ring {
         }
     }
 
+    /// The segment closest to `point`, with its index and distance, found
+    /// through the segment index.
+    pub(crate) fn nearest_segment_to_point(&self, point: Point) -> Option<(Segment, usize, f64)> {
+        let mut visitor = NearestToPoint::new(point);
+        self.nearest_segment(&mut visitor);
+        visitor.nearest
+    }
+
     pub fn line_search<V: SearchVisitor>(&self, other: &Line, visitor: &mut V) {
         extern "C" fn visit<V: SearchVisitor>(
             a_seg: tg_segment,
@@ -305,6 +389,20 @@ impl Ring {
     }
 }
 
+/// Coordinate mapping
+impl Ring {
+    /// Create a new ring with every point passed through `f`.
+    pub fn map_points<F: FnMut(Point) -> Point>(&self, f: F) -> Ring {
+        self.map_points_indexed(f, IndexType::Default)
+    }
+
+    /// Like [`Ring::map_points`], but with the given index.
+    pub fn map_points_indexed<F: FnMut(Point) -> Point>(&self, f: F, index: IndexType) -> Ring {
+        let points: Vec<Point> = self.points().iter().copied().map(f).collect();
+        Ring::new_indexed(&points, index)
+    }
+}
+
 #[cfg(feature = "atomics")]
 unsafe impl Send for Ring {}
 #[cfg(feature = "atomics")]
@@ -334,6 +432,32 @@ impl fmt::Debug for Ring {
     }
 }
 
+/// Equality
+impl Ring {
+    /// Whether the two rings run through the same vertices in the same cyclic
+    /// order, regardless of which vertex they start at, which direction they
+    /// run in, or whether they repeat their first point at the end.
+    ///
+    /// [`PartialEq`] compares the points exactly instead.
+    pub fn eq_topo(&self, other: &Ring) -> bool {
+        let (a, b) = (self.vertices(), other.vertices());
+        if a.len() != b.len() {
+            return false;
+        }
+        if a.is_empty() {
+            // too few points to be a proper ring
+            return self.points() == other.points();
+        }
+        let n = b.len();
+        (0..n).filter(|&k| b[k] == a[0]).any(|k| {
+            (0..n).all(|i| a[i] == b[(k + i) % n]) || (0..n).all(|i| a[i] == b[(k + n - i) % n])
+        })
+    }
+}
+
+/// Compares the points exactly, so rings which start at different vertices or
+/// run in opposite directions are not equal. Use [`Ring::eq_topo`] to ignore
+/// those differences.
 impl PartialEq for Ring {
     fn eq(&self, other: &Self) -> bool {
         self.points() == other.points()
@@ -343,7 +467,7 @@ impl PartialEq for Ring {
 #[cfg(test)]
 mod tests {
     use super::Ring;
-    use crate::Point;
+    use crate::{ConstructionError, IndexType, Point};
     #[test]
     fn clones_and_duplicates_are_equal() {
         let ring = Ring::new(&[
@@ -357,4 +481,85 @@ mod tests {
         assert_eq!(ring, ring.duplicate());
         assert_eq!(ring.clone(), ring.duplicate());
     }
+
+    #[test]
+    fn topological_equality() {
+        let square = [
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(1., 1.),
+            Point::new(0., 1.),
+            Point::new(0., 0.),
+        ];
+        let ring = Ring::new(&square);
+        let rotated = Ring::new(&[
+            Point::new(1., 1.),
+            Point::new(0., 1.),
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+        ]);
+        let mut points = square.to_vec();
+        points.reverse();
+        let reversed = Ring::new(&points);
+        assert_ne!(ring, rotated);
+        assert_ne!(ring, reversed);
+        assert!(ring.eq_topo(&rotated));
+        assert!(ring.eq_topo(&reversed));
+        assert!(rotated.eq_topo(&reversed));
+
+        let bowtie = Ring::new(&[
+            Point::new(0., 0.),
+            Point::new(1., 0.),
+            Point::new(0., 1.),
+            Point::new(1., 1.),
+            Point::new(0., 0.),
+        ]);
+        assert!(!ring.eq_topo(&bowtie));
+        assert!(!ring.eq_topo(&Ring::new(&square[..3])));
+    }
+
+    #[test]
+    fn checked_construction() {
+        let open = [Point::new(0., 0.), Point::new(1., 0.), Point::new(1., 1.)];
+        assert_eq!(
+            Ring::try_from_points(&open, false),
+            Err(ConstructionError::UnclosedRing {
+                first: Point::new(0., 0.),
+                last: Point::new(1., 1.)
+            })
+        );
+        let ring = Ring::try_from_points(&open, true).unwrap();
+        assert_eq!(ring.num_points(), 4);
+        assert_eq!(ring.point(3), Some(Point::new(0., 0.)));
+
+        assert_eq!(
+            Ring::try_from_points(&open[..2], true),
+            Err(ConstructionError::TooFewPoints { count: 2, min: 3 })
+        );
+        assert_eq!(
+            Ring::try_from_points(&[], true),
+            Err(ConstructionError::TooFewPoints { count: 0, min: 3 })
+        );
+        let nan = [Point::new(0., 0.), Point::new(f64::NAN, 0.), Point::new(1., 1.)];
+        assert!(matches!(
+            Ring::try_from_points(&nan, false),
+            Err(ConstructionError::NonFiniteCoordinate { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn map_points_indexed() {
+        let circle: Vec<Point> = (0..=100)
+            .map(|i| {
+                let angle = std::f64::consts::TAU * (i % 100) as f64 / 100.;
+                Point::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        let shift = |p: Point| Point::new(p.x + 1., p.y);
+        let ring = Ring::new_indexed(&circle, IndexType::None);
+        assert!(ring.map_points(shift).index_num_levels() > 0);
+        let shifted = ring.map_points_indexed(shift, IndexType::None);
+        assert_eq!(shifted.index_num_levels(), 0);
+        assert_eq!(shifted.point(0), Some(Point::new(2., 0.)));
+    }
 }
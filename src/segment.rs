@@ -5,14 +5,14 @@
 //! - [x] Add SegmentFuncs
 //! - [x] Standard traits
 //! - [ ] Serde traits
-//! - [ ] Should reversed segments be equal?
+//! - [x] Should reversed segments be equal?
 //! - [ ] Documentation
 
 use core::fmt;
 
 use tg_sys::{tg_segment, SegmentFuncs};
 
-use crate::{Point, Rect};
+use crate::{point::cross, Point, Rect};
 
 #[repr(transparent)]
 #[derive(Clone, Copy)]
@@ -77,6 +77,119 @@ impl Segment {
     }
 }
 
+/// Geometric measurements
+impl Segment {
+    pub fn length(self) -> f64 {
+        self.a().distance(self.b())
+    }
+
+    /// The point on the segment closest to `point`.
+    pub fn closest_point(self, point: Point) -> Point {
+        let (a, b) = (self.a(), self.b());
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let len2 = dx * dx + dy * dy;
+        if len2 == 0. {
+            return a;
+        }
+        let t = (((point.x - a.x) * dx + (point.y - a.y) * dy) / len2).clamp(0., 1.);
+        Point::new(a.x + t * dx, a.y + t * dy)
+    }
+
+    /// The euclidean distance from `point` to the closest point on the segment.
+    pub fn distance_to_point(self, point: Point) -> f64 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// The euclidean distance between the closest points of the two
+    /// segments, which is zero if they intersect.
+    pub fn distance_to_segment(self, other: Segment) -> f64 {
+        self.closest_points(other).0
+    }
+
+    /// The distance between the two segments, and the closest points on
+    /// `self` and `other`.
+    pub(crate) fn closest_points(self, other: Segment) -> (f64, Point, Point) {
+        if let Some(point) = self.intersection(other) {
+            return (0., point, point);
+        }
+        [
+            (other.closest_point(self.a()), self.a()),
+            (other.closest_point(self.b()), self.b()),
+            (other.a(), self.closest_point(other.a())),
+            (other.b(), self.closest_point(other.b())),
+        ]
+        .into_iter()
+        .map(|(on_other, on_self)| (on_self.distance(on_other), on_self, on_other))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .expect("there are four candidates")
+    }
+
+    /// The euclidean distance from the segment to the rect, which is zero if
+    /// they intersect.
+    pub(crate) fn distance_to_rect(self, rect: Rect) -> f64 {
+        if rect.intersects_point(self.a()) || rect.intersects_point(self.b()) {
+            return 0.;
+        }
+        let (min, max) = (rect.min(), rect.max());
+        let corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+        (0..4)
+            .map(|i| self.distance_to_segment(Segment::new(corners[i], corners[(i + 1) % 4])))
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    /// Where `self` and the segment `next`, which starts where `self` ends,
+    /// overlap beyond their shared vertex, if they do.
+    pub(crate) fn fold_back(self, next: Segment) -> Option<Point> {
+        let (a, v, b) = (self.a(), self.b(), next.b());
+        let collinear = cross(a, v, b) == 0.;
+        let backwards = (a.x - v.x) * (b.x - v.x) + (a.y - v.y) * (b.y - v.y) > 0.;
+        (collinear && backwards).then(|| if a.distance(v) < b.distance(v) { a } else { b })
+    }
+
+    /// Whether `point` lies exactly on the segment.
+    pub(crate) fn contains_point(self, point: Point) -> bool {
+        cross(self.a(), self.b(), point) == 0. && self.rect().intersects_point(point)
+    }
+
+    /// The point where the two segments intersect, if they do.
+    ///
+    /// When the segments are collinear and overlap, this is the first point of
+    /// the overlap along `self`.
+    pub fn intersection(self, other: Segment) -> Option<Point> {
+        if !self.intersects_segment(other) {
+            return None;
+        }
+        let (p, q) = (self.a(), other.a());
+        let (rx, ry) = (self.b().x - p.x, self.b().y - p.y);
+        let (sx, sy) = (other.b().x - q.x, other.b().y - q.y);
+        let (qpx, qpy) = (q.x - p.x, q.y - p.y);
+        let denom = rx * sy - ry * sx;
+        let rr = rx * rx + ry * ry;
+        let t = if denom != 0. {
+            (qpx * sy - qpy * sx) / denom
+        } else if rr == 0. {
+            0.
+        } else {
+            // collinear: the start of the overlap of the projected intervals
+            let t0 = (qpx * rx + qpy * ry) / rr;
+            let t1 = t0 + (sx * rx + sy * ry) / rr;
+            t0.min(t1)
+        };
+        let t = t.clamp(0., 1.);
+        Some(Point::new(p.x + t * rx, p.y + t * ry))
+    }
+}
+
+/// Equality
+impl Segment {
+    /// Whether the segments have the same endpoints, in either order.
+    ///
+    /// [`PartialEq`] requires the endpoints to be in the same order.
+    pub fn eq_undirected(self, other: Segment) -> bool {
+        self == other || (self.a() == other.b() && self.b() == other.a())
+    }
+}
+
 impl Default for Segment {
     fn default() -> Self {
         Self::new(Point::default(), Point::default())
@@ -92,6 +205,8 @@ impl fmt::Debug for Segment {
     }
 }
 
+/// Compares the endpoints in order, so reversed segments are not equal. Use
+/// [`Segment::eq_undirected`] to ignore direction.
 impl PartialEq for Segment {
     fn eq(&self, other: &Self) -> bool {
         self.a() == other.a() && self.b() == other.b()
@@ -109,3 +224,46 @@ impl From<tg_segment> for Segment {
         Segment::from_raw(value)
     }
 }
+
+/// The segments of a line, or of a ring when `closed`, for finding where it
+/// touches itself.
+///
+/// Zero-length segments, from repeated points, never count as touching, and
+/// segments separated only by them are treated as neighbors.
+pub(crate) struct SegmentChain {
+    /// The number of segments of non-zero length before each segment.
+    nonzero_before: Vec<usize>,
+    closed: bool,
+}
+
+impl SegmentChain {
+    pub(crate) fn new(segments: impl IntoIterator<Item = Segment>, closed: bool) -> SegmentChain {
+        let mut nonzero_before = vec![0];
+        for segment in segments {
+            let count = nonzero_before[nonzero_before.len() - 1];
+            nonzero_before.push(count + usize::from(segment.a() != segment.b()));
+        }
+        SegmentChain {
+            nonzero_before,
+            closed,
+        }
+    }
+
+    /// Where segments `a` and `b`, at indexes `i < j`, meet other than at
+    /// the vertex shared by neighbors.
+    pub(crate) fn touch(&self, a: Segment, i: usize, b: Segment, j: usize) -> Option<Point> {
+        if a.a() == a.b() || b.a() == b.b() {
+            return None;
+        }
+        let total = self.nonzero_before[self.nonzero_before.len() - 1];
+        if self.nonzero_before[j] == self.nonzero_before[i + 1] {
+            // Neighbors always share a vertex. They only intersect further
+            // if the chain folds back on itself there.
+            a.fold_back(b)
+        } else if self.closed && self.nonzero_before[i] + total - self.nonzero_before[j + 1] == 0 {
+            b.fold_back(a)
+        } else {
+            a.intersection(b)
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Similarity measures between lines: Hausdorff and discrete Fréchet
+//! distance.
+//!
+//! Both are computed from the vertices of the lines. Densifying adds evenly
+//! spaced points along each segment first, which brings the result closer to
+//! the continuous measure at the cost of more work.
+//!
+//! - The Hausdorff distance is the farthest any point of one geometry is from
+//!   the other. Each point is looked up in the other geometry's segment
+//!   index, so it takes O(n log m).
+//! - The Fréchet distance also takes the order of the points into account, so
+//!   a line is not similar to its reverse. It takes O(n m) time.
+
+use crate::{
+    distance::{distance_to_point, parts},
+    Geom, Line, Point,
+};
+
+/// Similarity
+impl Line {
+    /// The discrete Hausdorff distance between the two lines: the farthest a
+    /// vertex of either line is from the other line.
+    ///
+    /// Returns infinity if either line is empty.
+    pub fn hausdorff_distance(&self, other: &Line) -> f64 {
+        hausdorff(self, other, |points| points.to_vec())
+    }
+
+    /// The discrete Hausdorff distance, with every segment split into pieces
+    /// at most `fraction` of its length first.
+    ///
+    /// # Panics
+    ///
+    /// If `fraction` is not in `(0, 1]`.
+    pub fn hausdorff_distance_densified(&self, other: &Line, fraction: f64) -> f64 {
+        let pieces = pieces(fraction);
+        hausdorff(self, other, |points| densify(points, pieces))
+    }
+
+    /// The discrete Fréchet distance between the two lines: the shortest
+    /// leash needed to walk both lines from start to end, moving forward from
+    /// vertex to vertex on either or both of them at each step.
+    ///
+    /// Returns infinity if either line is empty.
+    pub fn frechet_distance(&self, other: &Line) -> f64 {
+        frechet(self.points(), other.points())
+    }
+
+    /// The discrete Fréchet distance, with every segment split into pieces at
+    /// most `fraction` of its length first.
+    ///
+    /// # Panics
+    ///
+    /// If `fraction` is not in `(0, 1]`.
+    pub fn frechet_distance_densified(&self, other: &Line, fraction: f64) -> f64 {
+        let pieces = pieces(fraction);
+        frechet(&densify(self.points(), pieces), &densify(other.points(), pieces))
+    }
+}
+
+/// Similarity
+impl Geom {
+    /// The discrete Hausdorff distance between the two geometries: the
+    /// farthest a vertex of either geometry is from the other geometry.
+    ///
+    /// Vertices inside a polygon are at distance zero from it. Returns
+    /// infinity if either geometry is empty.
+    pub fn hausdorff_distance(&self, other: &Geom) -> f64 {
+        self.directed_hausdorff(other, 1).max(other.directed_hausdorff(self, 1))
+    }
+
+    /// The discrete Hausdorff distance, with every segment split into pieces
+    /// at most `fraction` of its length first.
+    ///
+    /// # Panics
+    ///
+    /// If `fraction` is not in `(0, 1]`.
+    pub fn hausdorff_distance_densified(&self, other: &Geom, fraction: f64) -> f64 {
+        let pieces = pieces(fraction);
+        self.directed_hausdorff(other, pieces)
+            .max(other.directed_hausdorff(self, pieces))
+    }
+
+    /// The farthest a vertex of `self` is from `other`.
+    fn directed_hausdorff(&self, other: &Geom, pieces: usize) -> f64 {
+        if self.is_empty() || other.is_empty() {
+            return f64::INFINITY;
+        }
+        let targets = parts(other);
+        parts(self)
+            .iter()
+            .flat_map(|part| densify(part.points(), pieces))
+            .map(|point| {
+                if other.intersects_xy(point.x, point.y) {
+                    0.
+                } else {
+                    distance_to_point(&targets, point)
+                }
+            })
+            .fold(0., f64::max)
+    }
+}
+
+fn hausdorff(a: &Line, b: &Line, points: impl Fn(&[Point]) -> Vec<Point>) -> f64 {
+    if a.num_points() == 0 || b.num_points() == 0 {
+        return f64::INFINITY;
+    }
+    directed_hausdorff(&points(a.points()), b).max(directed_hausdorff(&points(b.points()), a))
+}
+
+/// The farthest any of `points` is from `line`.
+fn directed_hausdorff(points: &[Point], line: &Line) -> f64 {
+    points
+        .iter()
+        .map(|&point| match line.nearest_segment_to_point(point) {
+            Some((_, _, distance)) => distance,
+            // a single point
+            None => line.points()[0].distance(point),
+        })
+        .fold(0., f64::max)
+}
+
+fn frechet(a: &[Point], b: &[Point]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return f64::INFINITY;
+    }
+    // The coupling distance up to each point of `b`, for the previous and
+    // current point of `a`.
+    let mut prev = vec![0.; b.len()];
+    let mut curr = vec![0.; b.len()];
+    for (i, &p) in a.iter().enumerate() {
+        for (j, &q) in b.iter().enumerate() {
+            let reach = match (i, j) {
+                (0, 0) => 0.,
+                (0, _) => curr[j - 1],
+                (_, 0) => prev[0],
+                _ => f64::min(prev[j], prev[j - 1]).min(curr[j - 1]),
+            };
+            curr[j] = f64::max(reach, p.distance(q));
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len() - 1]
+}
+
+/// The number of pieces to split each segment into.
+fn pieces(fraction: f64) -> usize {
+    assert!(
+        fraction > 0. && fraction <= 1.,
+        "densify fraction {fraction} is not in (0, 1]"
+    );
+    (1. / fraction).ceil() as usize
+}
+
+/// `points` with each segment split into `pieces` equal pieces.
+fn densify(points: &[Point], pieces: usize) -> Vec<Point> {
+    let Some(&last) = points.last() else {
+        return Vec::new();
+    };
+    let mut dense = Vec::with_capacity((points.len() - 1) * pieces + 1);
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        dense.extend((0..pieces).map(|k| {
+            let t = k as f64 / pieces as f64;
+            Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y))
+        }));
+    }
+    dense.push(last);
+    dense
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Geom, Line, Point, Poly, Ring};
+
+    fn line(points: &[(f64, f64)]) -> Line {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Line::new(&points)
+    }
+
+    #[test]
+    fn hausdorff() {
+        let planned = line(&[(0., 0.), (10., 0.), (20., 0.)]);
+        let observed = line(&[(0., 1.), (5., 3.), (20., -1.)]);
+        assert_eq!(planned.hausdorff_distance(&observed), 3.);
+        assert_eq!(observed.hausdorff_distance(&planned), 3.);
+        assert_eq!(planned.hausdorff_distance(&planned), 0.);
+
+        // `b` bulges 4 away from `a`.
+        let a = line(&[(0., 0.), (0., 10.)]);
+        let b = line(&[(0., 0.), (4., 5.), (0., 10.)]);
+        assert_eq!(a.hausdorff_distance(&b), 4.);
+        assert_eq!(line(&[(0., 0.), (10., 0.)]).hausdorff_distance(&line(&[(5., 5.)])), 50f64.sqrt());
+        assert_eq!(a.hausdorff_distance(&Line::new(&[])), f64::INFINITY);
+    }
+
+    #[test]
+    fn densified_hausdorff() {
+        // The vertices of each are on the other, but the middle of the line
+        // is far from both points.
+        let line = line(&[(0., 0.), (10., 0.)]);
+        let ends = Geom::new_multipoint(&[Point::new(0., 0.), Point::new(10., 0.)]);
+        assert_eq!(line.geom().hausdorff_distance(&ends), 0.);
+        assert_eq!(line.geom().hausdorff_distance_densified(&ends, 0.5), 5.);
+        let thirds = line.geom().hausdorff_distance_densified(&ends, 0.4);
+        assert!((thirds - 10. / 3.).abs() < 1e-12);
+
+        let bent = Line::new(&[Point::new(0., 1.), Point::new(5., 1.), Point::new(10., 3.)]);
+        assert!(line.hausdorff_distance_densified(&bent, 0.1) >= line.hausdorff_distance(&bent));
+    }
+
+    #[test]
+    fn frechet() {
+        let a = line(&[(0., 0.), (10., 0.), (20., 0.)]);
+        let b = line(&[(0., 1.), (10., 1.), (20., 1.)]);
+        assert_eq!(a.frechet_distance(&b), 1.);
+        // Hausdorff ignores direction but Fréchet doesn't.
+        let reversed = line(&[(20., 0.), (10., 0.), (0., 0.)]);
+        assert_eq!(a.hausdorff_distance(&reversed), 0.);
+        assert_eq!(a.frechet_distance(&reversed), 20.);
+
+        let sparse = line(&[(0., 0.), (20., 0.)]);
+        assert_eq!(a.frechet_distance(&sparse), 10.);
+        assert_eq!(a.frechet_distance_densified(&sparse, 0.5), 5.);
+        assert_eq!(a.frechet_distance_densified(&sparse, 0.25), 2.5);
+        assert_eq!(a.frechet_distance(&Line::new(&[])), f64::INFINITY);
+    }
+
+    #[test]
+    fn geometry_hausdorff() {
+        let square = Ring::new(&[
+            Point::new(0., 0.),
+            Point::new(10., 0.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+            Point::new(0., 0.),
+        ]);
+        let zone = Poly::from(square).geom();
+        let points = Geom::new_multipoint(&[Point::new(5., 5.), Point::new(13., 14.)]);
+        // (5, 5) is inside the zone, (13, 14) is 5 from its corner, and the
+        // corner (0, 0) is farthest from either point.
+        assert_eq!(points.hausdorff_distance(&zone), 50f64.sqrt());
+        assert_eq!(zone.hausdorff_distance(&zone), 0.);
+        let line = line(&[(0., 0.), (10., 0.)]).geom();
+        assert_eq!(
+            zone.hausdorff_distance_densified(&line, 0.5),
+            zone.hausdorff_distance(&line)
+        );
+        assert_eq!(zone.hausdorff_distance(&line), 10.);
+    }
+}
@@ -0,0 +1,347 @@
+//! Line, ring and polygon simplification.
+//!
+//! Two algorithms are provided:
+//!
+//! - Douglas-Peucker (`simplify`), which drops points closer than a distance
+//!   tolerance to the simplified shape.
+//! - Visvalingam-Whyatt (`simplify_vw`), which repeatedly drops the point
+//!   forming the smallest triangle with its neighbors until every remaining
+//!   triangle has at least the given area.
+//!
+//! Both always keep the first and last point of a line. Rings stay closed and
+//! keep at least three distinct vertices, so the result is never degenerate.
+//! Simplification does not check topology: a heavily simplified polygon can
+//! self-intersect or have holes which cross its exterior.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{point::cross, IndexType, Line, Point, Poly, Ring, Segment};
+
+/// Douglas-Peucker simplification
+impl Line {
+    /// The result gets tg's default index. Use [`Line::simplify_indexed`] to
+    /// choose another.
+    pub fn simplify(&self, tolerance: f64) -> Line {
+        self.simplify_indexed(tolerance, IndexType::Default)
+    }
+
+    pub fn simplify_indexed(&self, tolerance: f64, index: IndexType) -> Line {
+        let points = self.points();
+        let points = select(points, &dp_weights(points), tolerance, 0);
+        Line::new_indexed(&points, index)
+    }
+}
+
+/// Visvalingam-Whyatt simplification
+impl Line {
+    /// The result gets tg's default index. Use [`Line::simplify_vw_indexed`] to
+    /// choose another.
+    pub fn simplify_vw(&self, area_threshold: f64) -> Line {
+        self.simplify_vw_indexed(area_threshold, IndexType::Default)
+    }
+
+    pub fn simplify_vw_indexed(&self, area_threshold: f64, index: IndexType) -> Line {
+        let points = self.points();
+        let points = select(points, &vw_weights(points), area_threshold, 0);
+        Line::new_indexed(&points, index)
+    }
+}
+
+/// Douglas-Peucker simplification
+impl Ring {
+    /// The result gets tg's default index. Use [`Ring::simplify_indexed`] to
+    /// choose another.
+    pub fn simplify(&self, tolerance: f64) -> Ring {
+        self.simplify_indexed(tolerance, IndexType::Default)
+    }
+
+    pub fn simplify_indexed(&self, tolerance: f64, index: IndexType) -> Ring {
+        simplify_ring(self, tolerance, index, dp_weights)
+    }
+}
+
+/// Visvalingam-Whyatt simplification
+impl Ring {
+    /// The result gets tg's default index. Use [`Ring::simplify_vw_indexed`] to
+    /// choose another.
+    pub fn simplify_vw(&self, area_threshold: f64) -> Ring {
+        self.simplify_vw_indexed(area_threshold, IndexType::Default)
+    }
+
+    pub fn simplify_vw_indexed(&self, area_threshold: f64, index: IndexType) -> Ring {
+        simplify_ring(self, area_threshold, index, vw_weights)
+    }
+}
+
+/// Douglas-Peucker simplification
+impl Poly {
+    /// Simplify the exterior and each hole independently.
+    ///
+    /// The result gets tg's default index. Use [`Poly::simplify_indexed`] to
+    /// choose another.
+    pub fn simplify(&self, tolerance: f64) -> Poly {
+        self.simplify_indexed(tolerance, IndexType::Default)
+    }
+
+    pub fn simplify_indexed(&self, tolerance: f64, index: IndexType) -> Poly {
+        let exterior = self.exterior().simplify_indexed(tolerance, index);
+        let holes: Vec<Ring> = self
+            .holes()
+            .iter()
+            .map(|hole| hole.simplify_indexed(tolerance, index))
+            .collect();
+        Poly::new(&exterior, &holes)
+    }
+}
+
+/// Visvalingam-Whyatt simplification
+impl Poly {
+    /// Simplify the exterior and each hole independently.
+    ///
+    /// The result gets tg's default index. Use [`Poly::simplify_vw_indexed`] to
+    /// choose another.
+    pub fn simplify_vw(&self, area_threshold: f64) -> Poly {
+        self.simplify_vw_indexed(area_threshold, IndexType::Default)
+    }
+
+    pub fn simplify_vw_indexed(&self, area_threshold: f64, index: IndexType) -> Poly {
+        let exterior = self.exterior().simplify_vw_indexed(area_threshold, index);
+        let holes: Vec<Ring> = self
+            .holes()
+            .iter()
+            .map(|hole| hole.simplify_vw_indexed(area_threshold, index))
+            .collect();
+        Poly::new(&exterior, &holes)
+    }
+}
+
+/// The minimum number of distinct vertices of a valid ring.
+const MIN_RING_VERTICES: usize = 3;
+
+fn simplify_ring(
+    ring: &Ring,
+    threshold: f64,
+    index: IndexType,
+    weights: fn(&[Point]) -> Vec<f64>,
+) -> Ring {
+    let vertices = ring.vertices();
+    if vertices.len() <= MIN_RING_VERTICES {
+        return Ring::new_indexed(ring.points(), index);
+    }
+    // Simplify as a line which starts and ends at the first vertex, which
+    // keeps that vertex as a fixed anchor and the result closed.
+    let mut closed = vertices.to_vec();
+    closed.push(vertices[0]);
+    let points = select(&closed, &weights(&closed), threshold, MIN_RING_VERTICES + 1);
+    Ring::new_indexed(&points, index)
+}
+
+/// Keep the points whose weight is above `threshold`, but at least `min`
+/// points, preferring the ones with the largest weights.
+fn select(points: &[Point], weights: &[f64], threshold: f64, min: usize) -> Vec<Point> {
+    let mut keep: Vec<bool> = weights.iter().map(|&w| w > threshold).collect();
+    let kept = keep.iter().filter(|&&k| k).count();
+    if kept < min {
+        let mut rest: Vec<usize> = (0..points.len()).filter(|&i| !keep[i]).collect();
+        rest.sort_by(|&a, &b| weights[b].total_cmp(&weights[a]));
+        for i in rest.into_iter().take(min - kept) {
+            keep[i] = true;
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&point, keep)| keep.then_some(point))
+        .collect()
+}
+
+/// The Douglas-Peucker tolerance below which each point would be dropped.
+///
+/// The endpoints are never dropped. A point's weight is capped by the weights
+/// of the points which split the line before it, since Douglas-Peucker never
+/// looks at a sub-line once the split above it was rejected.
+fn dp_weights(points: &[Point]) -> Vec<f64> {
+    let mut weights = vec![f64::INFINITY; points.len()];
+    if points.len() < 3 {
+        return weights;
+    }
+    let mut stack = vec![(0, points.len() - 1, f64::INFINITY)];
+    while let Some((first, last, limit)) = stack.pop() {
+        if last <= first + 1 {
+            continue;
+        }
+        let segment = Segment::new(points[first], points[last]);
+        let (split, distance) = (first + 1..last)
+            .map(|i| (i, segment.distance_to_point(points[i])))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("sub-line has interior points");
+        let weight = distance.min(limit);
+        weights[split] = weight;
+        stack.push((first, split, weight));
+        stack.push((split, last, weight));
+    }
+    weights
+}
+
+/// The Visvalingam-Whyatt area below which each point would be dropped.
+///
+/// The endpoints are never dropped. A point's weight is at least the weight of
+/// any point dropped before it, so that dropping points in order of weight
+/// matches the iterative algorithm.
+fn vw_weights(points: &[Point]) -> Vec<f64> {
+    let n = points.len();
+    let mut weights = vec![f64::INFINITY; n];
+    if n < 3 {
+        return weights;
+    }
+    let mut prev: Vec<usize> = (0..n).map(|i| i.wrapping_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=n).collect();
+    let area = |prev: &[usize], next: &[usize], i: usize| {
+        triangle_area(points[prev[i]], points[i], points[next[i]])
+    };
+
+    let mut areas: Vec<f64> = (0..n)
+        .map(|i| match i {
+            0 => f64::INFINITY,
+            i if i == n - 1 => f64::INFINITY,
+            i => area(&prev, &next, i),
+        })
+        .collect();
+    let mut heap: BinaryHeap<Candidate> = (1..n - 1)
+        .map(|index| Candidate {
+            area: areas[index],
+            index,
+        })
+        .collect();
+
+    let mut max_area: f64 = 0.;
+    while let Some(Candidate { area: a, index }) = heap.pop() {
+        if a != areas[index] || weights[index] != f64::INFINITY {
+            // stale entry, superseded after a neighbor was dropped
+            continue;
+        }
+        max_area = max_area.max(a);
+        weights[index] = max_area;
+        let (p, q) = (prev[index], next[index]);
+        next[p] = q;
+        prev[q] = p;
+        for neighbor in [p, q] {
+            if neighbor != 0 && neighbor != n - 1 {
+                areas[neighbor] = area(&prev, &next, neighbor);
+                heap.push(Candidate {
+                    area: areas[neighbor],
+                    index: neighbor,
+                });
+            }
+        }
+    }
+    weights
+}
+
+fn triangle_area(a: Point, b: Point, c: Point) -> f64 {
+    cross(a, b, c).abs() / 2.
+}
+
+/// A heap entry ordered so that the smallest area is popped first.
+struct Candidate {
+    area: f64,
+    index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IndexType, Line, Point, Poly, Ring};
+
+    fn zigzag() -> Line {
+        Line::new(&[
+            Point::new(0., 0.),
+            Point::new(1., 0.1),
+            Point::new(2., -0.1),
+            Point::new(3., 5.),
+            Point::new(4., 6.),
+            Point::new(5., 7.),
+            Point::new(6., 8.1),
+            Point::new(7., 9.),
+        ])
+    }
+
+    #[test]
+    fn douglas_peucker_line() {
+        let line = zigzag();
+        let simple = line.simplify(0.5);
+        assert_eq!(
+            simple.points(),
+            &[
+                Point::new(0., 0.),
+                Point::new(2., -0.1),
+                Point::new(3., 5.),
+                Point::new(7., 9.),
+            ]
+        );
+        let collinear = Line::new(&[Point::new(0., 0.), Point::new(1., 1.), Point::new(2., 2.)]);
+        assert_eq!(collinear.simplify(0.).num_points(), 2);
+        assert_eq!(line.simplify(100.).points(), &[Point::new(0., 0.), Point::new(7., 9.)]);
+    }
+
+    #[test]
+    fn visvalingam_line() {
+        let line = zigzag();
+        let simple = line.simplify_vw(0.2);
+        assert_eq!(simple.point(0), Some(Point::new(0., 0.)));
+        assert_eq!(simple.point(simple.num_points() - 1), Some(Point::new(7., 9.)));
+        assert!(simple.points().contains(&Point::new(3., 5.)));
+        assert!(!simple.points().contains(&Point::new(4., 6.)));
+        assert_eq!(line.simplify_vw(1000.).num_points(), 2);
+    }
+
+    #[test]
+    fn rings_stay_valid() {
+        let mut points: Vec<Point> = (0..64)
+            .map(|i| {
+                let theta = i as f64 / 64. * std::f64::consts::TAU;
+                Point::new(theta.cos(), theta.sin())
+            })
+            .collect();
+        points.push(points[0]);
+        let ring = Ring::new(&points);
+        for simple in [ring.simplify(10.), ring.simplify_vw(10.)] {
+            assert_eq!(simple.num_points(), 4);
+            assert_eq!(simple.point(0), simple.point(3));
+        }
+        let simple = ring.simplify_indexed(0.01, IndexType::YStripes);
+        assert!(simple.num_points() < ring.num_points());
+        assert_eq!(simple.point(0), simple.point(simple.num_points() - 1));
+        assert!(simple.index_num_levels() > 0);
+        let unindexed = ring.simplify_vw_indexed(0.0001, IndexType::None);
+        assert_eq!(unindexed.index_num_levels(), 0);
+
+        let poly = Poly::new(&ring, &[ring.map_points(|p| Point::new(p.x / 2., p.y / 2.))]);
+        let simple = poly.simplify_vw(0.01);
+        assert_eq!(simple.num_holes(), 1);
+        assert!(simple.exterior().num_points() < ring.num_points());
+        assert!(simple.hole(0).unwrap().num_points() >= 4);
+    }
+}
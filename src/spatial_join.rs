@@ -0,0 +1,185 @@
+//! Spatial joins between two collections of geometries.
+//!
+//! The smaller collection goes into a [`GeomIndex`], and each geometry of the
+//! other looks up the index for candidates, which are checked with tg's
+//! predicates. With the `atomics` feature, which makes geometries safe to
+//! share between threads, the lookups are split across threads.
+
+use std::ops::Range;
+
+use crate::{Geom, GeomIndex, Point, Rect};
+
+/// How geometries must relate to be joined.
+///
+/// Each predicate is applied with the geometry from the left collection
+/// first, so [`Predicate::Contains`] joins left geometries to the right
+/// geometries they contain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Predicate {
+    Intersects,
+    Contains,
+    Within,
+    /// Within the distance of each other, as in [`Geom::dwithin`].
+    DWithin(f64),
+}
+
+impl Predicate {
+    fn holds(self, left: &Geom, right: &Geom) -> bool {
+        match self {
+            Predicate::Intersects => left.intersects(right),
+            Predicate::Contains => left.contains(right),
+            Predicate::Within => left.within(right),
+            Predicate::DWithin(distance) => left.dwithin(right, distance),
+        }
+    }
+
+    /// The rect candidates for `geom` must intersect.
+    fn search_rect(self, geom: &Geom) -> Rect {
+        let rect = geom.rect();
+        match self {
+            Predicate::DWithin(distance) if distance > 0. => {
+                let (min, max) = (rect.min(), rect.max());
+                Rect::new(
+                    Point::new(min.x - distance, min.y - distance),
+                    Point::new(max.x + distance, max.y + distance),
+                )
+            }
+            _ => rect,
+        }
+    }
+}
+
+/// The pairs of indices into `left` and `right` whose geometries satisfy
+/// `predicate`, ordered by the left index and then the right.
+pub fn spatial_join(left: &[Geom], right: &[Geom], predicate: Predicate) -> impl Iterator<Item = (usize, usize)> {
+    // Index the smaller side, and look up each geometry of the larger.
+    let index_left = left.len() < right.len();
+    let (indexed, probes) = if index_left { (left, right) } else { (right, left) };
+    let index: GeomIndex<usize> = indexed.iter().cloned().enumerate().map(|(i, geom)| (geom, i)).collect();
+    let join = Join {
+        index: &index,
+        probes,
+        index_left,
+        predicate,
+    };
+    let mut pairs = join.run();
+    pairs.sort_unstable();
+    pairs.into_iter()
+}
+
+struct Join<'a> {
+    index: &'a GeomIndex<usize>,
+    probes: &'a [Geom],
+    /// Whether the index holds the left geometries.
+    index_left: bool,
+    predicate: Predicate,
+}
+
+impl Join<'_> {
+    /// The matching pairs for the probes in `range`, as left and right
+    /// indices.
+    fn probe(&self, range: Range<usize>) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for p in range {
+            let probe = &self.probes[p];
+            if probe.is_empty() {
+                continue;
+            }
+            for i in self.index.search_rect(self.predicate.search_rect(probe)) {
+                let (geom, _) = self.index.get(i).expect("search returns indices in the index");
+                let pair = if self.index_left {
+                    self.predicate.holds(geom, probe).then_some((i, p))
+                } else {
+                    self.predicate.holds(probe, geom).then_some((p, i))
+                };
+                pairs.extend(pair);
+            }
+        }
+        pairs
+    }
+
+    #[cfg(not(feature = "atomics"))]
+    fn run(&self) -> Vec<(usize, usize)> {
+        self.probe(0..self.probes.len())
+    }
+
+    /// Probe in chunks on as many threads as are available, or on this one
+    /// when there are only a few probes.
+    #[cfg(feature = "atomics")]
+    fn run(&self) -> Vec<(usize, usize)> {
+        const MIN_CHUNK: usize = 256;
+        let n = self.probes.len();
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(n / MIN_CHUNK);
+        if threads <= 1 {
+            return self.probe(0..n);
+        }
+        let chunk = n.div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..n)
+                .step_by(chunk)
+                .map(|start| scope.spawn(move || self.probe(start..(start + chunk).min(n))))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("join threads do not panic"))
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spatial_join, Predicate};
+    use crate::test_util::square;
+    use crate::{Geom, Point};
+
+    fn nested_loops(left: &[Geom], right: &[Geom], predicate: Predicate) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, a) in left.iter().enumerate() {
+            for (j, b) in right.iter().enumerate() {
+                if predicate.holds(a, b) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn matches_nested_loops() {
+        // Enough points to be split across threads, against overlapping
+        // zones.
+        let points: Vec<Geom> = (0..2000)
+            .map(|i| Point::new((i * 37 % 101) as f64 / 2., (i * 53 % 89) as f64 / 2.).geom())
+            .collect();
+        let zones: Vec<Geom> = (0..40)
+            .map(|i| square((i % 8) as f64 * 6., (i / 8) as f64 * 9., 4. + (i % 3) as f64 * 2.).geom())
+            .collect();
+        for predicate in [
+            Predicate::Intersects,
+            Predicate::Contains,
+            Predicate::Within,
+            Predicate::DWithin(1.5),
+        ] {
+            let expected = nested_loops(&zones, &points, predicate);
+            let joined: Vec<_> = spatial_join(&zones, &points, predicate).collect();
+            assert_eq!(joined, expected, "{predicate:?}");
+            let expected = nested_loops(&points, &zones, predicate);
+            let joined: Vec<_> = spatial_join(&points, &zones, predicate).collect();
+            assert_eq!(joined, expected, "{predicate:?}");
+        }
+        let contained = spatial_join(&zones, &points, Predicate::Contains).count();
+        assert!(contained > 100);
+    }
+
+    #[test]
+    fn empty_sides() {
+        let zones = [square(0., 0., 1.).geom()];
+        assert_eq!(spatial_join(&zones, &[], Predicate::Intersects).count(), 0);
+        assert_eq!(spatial_join(&[], &zones, Predicate::Intersects).count(), 0);
+        let empty = [Geom::new_multipoint(&[])];
+        assert_eq!(spatial_join(&empty, &zones, Predicate::DWithin(10.)).count(), 0);
+    }
+}
@@ -0,0 +1,15 @@
+//! Fixtures shared by the unit tests.
+
+use crate::{Point, Ring};
+
+/// The axis-aligned square with its lower left corner at `(x, y)`, wound
+/// counter-clockwise.
+pub(crate) fn square(x: f64, y: f64, size: f64) -> Ring {
+    Ring::new(&[
+        Point::new(x, y),
+        Point::new(x + size, y),
+        Point::new(x + size, y + size),
+        Point::new(x, y + size),
+        Point::new(x, y),
+    ])
+}
@@ -0,0 +1,288 @@
+//! Web map tiles in the XYZ scheme used by OpenStreetMap and most web maps.
+//!
+//! At zoom `z` the Web Mercator world is divided into `2^z × 2^z` square
+//! tiles, with `x` growing east from the antimeridian and `y` growing south
+//! from [`MAX_LATITUDE`](crate::projection::MAX_LATITUDE). Geometries are in
+//! lon/lat degrees, as in [`projection`](crate::projection).
+
+use std::fmt;
+
+use crate::{
+    projection::{lonlat_to_web_mercator, web_mercator_to_lonlat, MAX_EXTENT},
+    Geom, GeomType, Line, Point, Poly, Rect, Ring,
+};
+
+/// The deepest zoom level, at which tile coordinates still fit in a `u32`.
+pub const MAX_ZOOM: u8 = 32;
+
+/// A web map tile.
+///
+/// `x` and `y` must be less than `2^z`, and `z` at most [`MAX_ZOOM`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tile {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Tile {
+    pub fn new(z: u8, x: u32, y: u32) -> Tile {
+        Tile { z, x, y }
+    }
+
+    /// The tile at zoom `z` containing the lon/lat `point`.
+    ///
+    /// Points beyond the edges of the world are clamped to the nearest tile.
+    ///
+    /// # Panics
+    ///
+    /// If `z` is greater than [`MAX_ZOOM`].
+    pub fn containing(point: Point, z: u8) -> Tile {
+        let n = tiles_per_side(z) as f64;
+        let point = lonlat_to_web_mercator(point);
+        let index = |fraction: f64| (fraction * n).clamp(0., n - 1.) as u32;
+        Tile {
+            z,
+            x: index((point.x + MAX_EXTENT) / (2. * MAX_EXTENT)),
+            y: index((MAX_EXTENT - point.y) / (2. * MAX_EXTENT)),
+        }
+    }
+
+    /// The tile's extent in Web Mercator meters.
+    pub fn web_mercator_rect(self) -> Rect {
+        let size = 2. * MAX_EXTENT / tiles_per_side(self.z) as f64;
+        Rect::new(
+            Point::new(-MAX_EXTENT + self.x as f64 * size, MAX_EXTENT - (self.y as f64 + 1.) * size),
+            Point::new(-MAX_EXTENT + (self.x as f64 + 1.) * size, MAX_EXTENT - self.y as f64 * size),
+        )
+    }
+
+    /// The tile's extent in lon/lat degrees.
+    pub fn rect(self) -> Rect {
+        let rect = self.web_mercator_rect();
+        // Longitudes are computed directly, so that tile edges fall exactly on
+        // the antimeridian and the prime meridian.
+        let n = tiles_per_side(self.z) as f64;
+        let lon = |x: f64| x / n * 360. - 180.;
+        let lat = |y: f64| web_mercator_to_lonlat(Point::new(0., y)).y;
+        Rect::new(
+            Point::new(lon(self.x as f64), lat(rect.min().y)),
+            Point::new(lon(self.x as f64 + 1.), lat(rect.max().y)),
+        )
+    }
+
+    /// The tile one zoom level up which contains this one, or `None` at zoom
+    /// 0.
+    pub fn parent(self) -> Option<Tile> {
+        (self.z > 0).then(|| Tile::new(self.z - 1, self.x >> 1, self.y >> 1))
+    }
+
+    /// The four tiles one zoom level down, in quadkey order.
+    ///
+    /// # Panics
+    ///
+    /// If the tile is already at [`MAX_ZOOM`].
+    pub fn children(self) -> [Tile; 4] {
+        assert!(self.z < MAX_ZOOM, "tiles at zoom {MAX_ZOOM} have no children");
+        let (z, x, y) = (self.z + 1, self.x << 1, self.y << 1);
+        [Tile::new(z, x, y), Tile::new(z, x + 1, y), Tile::new(z, x, y + 1), Tile::new(z, x + 1, y + 1)]
+    }
+
+    /// The tile's Bing Maps quadkey, with one digit per zoom level.
+    pub fn quadkey(self) -> String {
+        (1..=self.z)
+            .rev()
+            .map(|bit| {
+                let digit = ((self.x >> (bit - 1)) & 1) | (((self.y >> (bit - 1)) & 1) << 1);
+                char::from(b'0' + digit as u8)
+            })
+            .collect()
+    }
+
+    /// The tile with the given quadkey, or `None` if it contains characters
+    /// other than `0`–`3` or is longer than [`MAX_ZOOM`].
+    ///
+    /// The empty quadkey is the single tile at zoom 0.
+    pub fn from_quadkey(quadkey: &str) -> Option<Tile> {
+        if quadkey.len() > MAX_ZOOM as usize {
+            return None;
+        }
+        let mut tile = Tile::new(0, 0, 0);
+        for c in quadkey.bytes() {
+            let digit = match c {
+                b'0'..=b'3' => (c - b'0') as u32,
+                _ => return None,
+            };
+            tile = Tile::new(tile.z + 1, tile.x << 1 | digit & 1, tile.y << 1 | digit >> 1);
+        }
+        Some(tile)
+    }
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+/// Tile covers
+impl Geom {
+    /// The tiles at zoom `z` which the lon/lat geometry intersects, in
+    /// quadkey order.
+    ///
+    /// Each tile is tested against the geometry itself rather than its
+    /// bounding rect, so a diagonal line only covers the tiles it passes
+    /// through. Tiles count as intersected when the geometry only touches
+    /// their edge.
+    ///
+    /// # Panics
+    ///
+    /// If `z` is greater than [`MAX_ZOOM`].
+    pub fn tile_cover(&self, z: u8) -> Vec<Tile> {
+        assert!(z <= MAX_ZOOM, "zoom {z} is deeper than {MAX_ZOOM}");
+        let mut tiles = Vec::new();
+        if !self.is_empty() {
+            let has_area = matches!(
+                self.geom_type(),
+                GeomType::Polygon | GeomType::MultiPolygon | GeomType::GeometryCollection
+            );
+            cover(self, has_area, Tile::new(0, 0, 0), z, &mut tiles);
+        }
+        tiles
+    }
+}
+
+/// Tile covers
+impl Line {
+    /// The tiles at zoom `z` which the lon/lat line passes through. See
+    /// [`Geom::tile_cover`].
+    pub fn tile_cover(&self, z: u8) -> Vec<Tile> {
+        self.geom().tile_cover(z)
+    }
+}
+
+/// Tile covers
+impl Ring {
+    /// The tiles at zoom `z` which the area of the lon/lat ring intersects.
+    /// See [`Geom::tile_cover`].
+    pub fn tile_cover(&self, z: u8) -> Vec<Tile> {
+        self.geom().tile_cover(z)
+    }
+}
+
+/// Tile covers
+impl Poly {
+    /// The tiles at zoom `z` which the lon/lat polygon intersects. See
+    /// [`Geom::tile_cover`].
+    pub fn tile_cover(&self, z: u8) -> Vec<Tile> {
+        self.geom().tile_cover(z)
+    }
+}
+
+fn tiles_per_side(z: u8) -> u64 {
+    assert!(z <= MAX_ZOOM, "zoom {z} is deeper than {MAX_ZOOM}");
+    1 << z
+}
+
+/// Add the tiles at zoom `z` under `tile` which `geom` intersects.
+fn cover(geom: &Geom, has_area: bool, tile: Tile, z: u8, tiles: &mut Vec<Tile>) {
+    let rect = tile.rect();
+    if !geom.intersects_rect(rect) {
+        return;
+    }
+    if tile.z == z {
+        tiles.push(tile);
+        return;
+    }
+    // Skip testing every tile inside a large polygon.
+    if has_area && geom.covers(&rect.geom()) {
+        descendants(tile, z, tiles);
+        return;
+    }
+    for child in tile.children() {
+        cover(geom, has_area, child, z, tiles);
+    }
+}
+
+/// Add all the tiles at zoom `z` under `tile`, in quadkey order.
+fn descendants(tile: Tile, z: u8, tiles: &mut Vec<Tile>) {
+    if tile.z == z {
+        tiles.push(tile);
+        return;
+    }
+    for child in tile.children() {
+        descendants(child, z, tiles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tile, MAX_ZOOM};
+    use crate::{projection::MAX_LATITUDE, Line, Point, Rect, Ring};
+
+    #[test]
+    fn tile_rects() {
+        let world = Tile::new(0, 0, 0).rect();
+        assert_eq!(world.min().x, -180.);
+        assert_eq!(world.max().x, 180.);
+        assert!((world.max().y - MAX_LATITUDE).abs() < 1e-9);
+
+        let tile = Tile::new(1, 1, 0).rect();
+        assert_eq!(tile.min(), Point::new(0., 0.));
+        assert!((tile.max().y - MAX_LATITUDE).abs() < 1e-9);
+
+        let london = Point::new(-0.1276, 51.5072);
+        let tile = Tile::containing(london, 10);
+        assert_eq!(tile, Tile::new(10, 511, 340));
+        assert!(tile.rect().intersects_point(london));
+        assert_eq!(tile.parent(), Some(Tile::new(9, 255, 170)));
+        assert!(tile.children().iter().all(|child| child.parent() == Some(tile)));
+        assert_eq!(Tile::containing(Point::new(180., -90.), 2), Tile::new(2, 3, 3));
+    }
+
+    #[test]
+    fn quadkeys() {
+        let tile = Tile::new(3, 3, 5);
+        assert_eq!(tile.quadkey(), "213");
+        assert_eq!(Tile::from_quadkey("213"), Some(tile));
+        assert_eq!(Tile::new(0, 0, 0).quadkey(), "");
+        assert_eq!(Tile::from_quadkey(""), Some(Tile::new(0, 0, 0)));
+        assert_eq!(Tile::from_quadkey("124"), None);
+        assert_eq!(Tile::from_quadkey(&"0".repeat(MAX_ZOOM as usize + 1)), None);
+
+        let deep = Tile::new(MAX_ZOOM, u32::MAX, 12345);
+        assert_eq!(Tile::from_quadkey(&deep.quadkey()), Some(deep));
+        assert_eq!(deep.rect().max().x, 180.);
+    }
+
+    #[test]
+    fn cover_line_exactly() {
+        // The line's bounding rect overlaps all four zoom 1 tiles, but it
+        // crosses the equator east of the prime meridian and so misses the
+        // north-west tile.
+        let line = Line::new(&[Point::new(-90., -45.), Point::new(90., 10.)]);
+        assert_eq!(
+            line.tile_cover(1),
+            vec![Tile::new(1, 1, 0), Tile::new(1, 0, 1), Tile::new(1, 1, 1)]
+        );
+        let tiles = line.tile_cover(4);
+        assert!(tiles.iter().all(|tile| line.geom().intersects_rect(tile.rect())));
+        assert!(tiles.len() < 16);
+    }
+
+    #[test]
+    fn cover_polygon() {
+        let rect = Rect::new(Point::new(-100., -60.), Point::new(100., 60.));
+        let (min, max) = (rect.min(), rect.max());
+        let ring = Ring::new(&[min, Point::new(max.x, min.y), max, Point::new(min.x, max.y), min]);
+        let tiles = ring.tile_cover(3);
+        // x from -100° to 100° spans tiles 1..=6, and y from 60° to -60°
+        // spans tiles 2..=5.
+        assert_eq!(tiles.len(), 6 * 4);
+        let mut sorted = tiles.clone();
+        sorted.sort_by_key(|tile| tile.quadkey());
+        assert_eq!(tiles, sorted);
+        assert!(ring.tile_cover(0) == vec![Tile::new(0, 0, 0)]);
+        assert!(Ring::new(&[]).tile_cover(5).is_empty());
+    }
+}
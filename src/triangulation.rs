@@ -0,0 +1,539 @@
+//! Delaunay triangulations and Voronoi diagrams of point sets.
+//!
+//! The triangulation is built by a radial sweep, ported from Mapbox's
+//! [delaunator]: points are added in order of distance from a seed triangle,
+//! each one joined to the part of the convex hull it can see, and edges are
+//! flipped until every triangle's circumcircle is empty. Voronoi cells are
+//! then cut out of a rect by the perpendicular bisectors between each point
+//! and its neighbors in the triangulation.
+//!
+//! [delaunator]: https://github.com/mapbox/delaunator
+
+use crate::{point::cross, Geom, Point, Poly, Rect, Ring};
+
+const NONE: usize = usize::MAX;
+
+/// The Delaunay triangulation of a set of points.
+///
+/// No point lies inside the circumcircle of any triangle, and the triangles
+/// cover the convex hull of the points. Non-finite points are ignored, as are
+/// repeats of earlier points. When fewer than three distinct points remain,
+/// or they are all collinear, there are no triangles.
+#[derive(Clone, Debug)]
+pub struct Delaunay {
+    points: Vec<Point>,
+    triangles: Vec<[usize; 3]>,
+    /// The distinct finite points, sorted by x and then y.
+    unique: Vec<usize>,
+    /// For each point, the index of the first point equal to it, or [`NONE`]
+    /// if it is not finite.
+    first: Vec<usize>,
+}
+
+impl Delaunay {
+    pub fn new(points: &[Point]) -> Delaunay {
+        let mut sorted: Vec<usize> = (0..points.len())
+            .filter(|&i| points[i].x.is_finite() && points[i].y.is_finite())
+            .collect();
+        sorted.sort_by(|&a, &b| {
+            let (a, b) = (points[a], points[b]);
+            a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+        });
+        let mut first = vec![NONE; points.len()];
+        let mut unique: Vec<usize> = Vec::with_capacity(sorted.len());
+        for i in sorted {
+            match unique.last() {
+                Some(&u) if points[u] == points[i] => first[i] = first[u],
+                _ => {
+                    first[i] = i;
+                    unique.push(i);
+                }
+            }
+        }
+        let triangles = Sweep::triangulate(points, &unique);
+        Delaunay {
+            points: points.to_vec(),
+            triangles,
+            unique,
+            first,
+        }
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// The indices into [`points`](Delaunay::points) of the corners of each
+    /// triangle, counter-clockwise.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Each triangle as a closed counter-clockwise ring.
+    pub fn rings(&self) -> Vec<Ring> {
+        self.triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = t.map(|i| self.points[i]);
+                Ring::new(&[a, b, c, a])
+            })
+            .collect()
+    }
+
+    /// The triangles as a multipolygon.
+    pub fn geom(&self) -> Geom {
+        let polys: Vec<Poly> = self.rings().into_iter().map(Poly::from).collect();
+        Geom::new_multipolygon(&polys)
+    }
+
+    /// The Voronoi cell of each point, clipped to `rect`.
+    ///
+    /// The cell of a point is the region closer to it than to any other
+    /// point. Cells line up with [`points`](Delaunay::points), and are `None`
+    /// for non-finite points and for cells which do not reach into `rect`.
+    /// Repeated points share the same cell.
+    pub fn voronoi_cells(&self, rect: Rect) -> Vec<Option<Poly>> {
+        let mut neighbors = vec![Vec::new(); self.points.len()];
+        if self.triangles.is_empty() {
+            // The points are collinear, so sorting them puts them in order
+            // along their line.
+            for pair in self.unique.windows(2) {
+                neighbors[pair[0]].push(pair[1]);
+                neighbors[pair[1]].push(pair[0]);
+            }
+        } else {
+            for t in &self.triangles {
+                for k in 0..3 {
+                    // Edges on the hull are in only one triangle, so add
+                    // both directions.
+                    neighbors[t[k]].push(t[(k + 1) % 3]);
+                    neighbors[t[(k + 1) % 3]].push(t[k]);
+                }
+            }
+            for list in &mut neighbors {
+                list.sort_unstable();
+                list.dedup();
+            }
+        }
+        let cells: Vec<Option<Poly>> = (0..self.points.len())
+            .map(|i| {
+                if self.first[i] != i {
+                    return None;
+                }
+                voronoi_cell(self.points[i], neighbors[i].iter().map(|&j| self.points[j]), rect)
+            })
+            .collect();
+        (0..self.points.len())
+            .map(|i| match self.first[i] {
+                NONE => None,
+                first => cells[first].clone(),
+            })
+            .collect()
+    }
+}
+
+/// The part of `rect` closer to `point` than to any of `neighbors`.
+fn voronoi_cell(point: Point, neighbors: impl Iterator<Item = Point>, rect: Rect) -> Option<Poly> {
+    let (min, max) = (rect.min(), rect.max());
+    let mut cell = vec![min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    for other in neighbors {
+        // Keep the side of the bisector where (p - mid) . (other - point) <= 0.
+        let mid = Point::new((point.x + other.x) / 2., (point.y + other.y) / 2.);
+        let (nx, ny) = (other.x - point.x, other.y - point.y);
+        let side = |p: Point| (p.x - mid.x) * nx + (p.y - mid.y) * ny;
+        let mut clipped = Vec::with_capacity(cell.len() + 1);
+        for (k, &a) in cell.iter().enumerate() {
+            let b = cell[(k + 1) % cell.len()];
+            let (sa, sb) = (side(a), side(b));
+            if sa <= 0. {
+                clipped.push(a);
+            }
+            if (sa < 0. && sb > 0.) || (sa > 0. && sb < 0.) {
+                let t = sa / (sa - sb);
+                clipped.push(Point::new(a.x + t * (b.x - a.x), a.y + t * (b.y - a.y)));
+            }
+        }
+        clipped.dedup();
+        if clipped.len() > 1 && clipped.first() == clipped.last() {
+            clipped.pop();
+        }
+        if clipped.len() < 3 {
+            return None;
+        }
+        cell = clipped;
+    }
+    cell.push(cell[0]);
+    Some(Poly::from(Ring::new(&cell)))
+}
+
+/// The state of delaunator's sweep.
+///
+/// Triangles are stored as three consecutive half-edges, the one at `e`
+/// starting at `triangles[e]`. Triangles here are clockwise, as delaunator
+/// works in screen coordinates, and are reversed on the way out.
+struct Sweep<'a> {
+    points: &'a [Point],
+    triangles: Vec<usize>,
+    /// The opposite half-edge of each half-edge, or [`NONE`] on the hull.
+    halfedges: Vec<usize>,
+    center: Point,
+    /// The hull as a circular list of point indices.
+    hull_start: usize,
+    hull_prev: Vec<usize>,
+    hull_next: Vec<usize>,
+    /// The half-edge of the triangle on each hull edge.
+    hull_tri: Vec<usize>,
+    /// Hull points bucketed by angle around the center, for finding the
+    /// hull edges a point can see.
+    hull_hash: Vec<usize>,
+    edge_stack: Vec<usize>,
+}
+
+impl Sweep<'_> {
+    /// The counter-clockwise triangles of the distinct points `ids`.
+    fn triangulate(points: &[Point], ids: &[usize]) -> Vec<[usize; 3]> {
+        if ids.len() < 3 {
+            return Vec::new();
+        }
+        let mut bounds = Rect::new(points[ids[0]], points[ids[0]]);
+        for &i in ids {
+            bounds = bounds.expand_point(points[i]);
+        }
+        let center = bounds.center();
+        let closest = |to: Point, skip: &[usize]| {
+            ids.iter()
+                .copied()
+                .filter(|i| !skip.contains(i))
+                .min_by(|&a, &b| points[a].distance(to).total_cmp(&points[b].distance(to)))
+                .expect("there are at least three points")
+        };
+        // Seed with the point nearest the center, its nearest neighbor, and
+        // the point making the smallest circumcircle with them.
+        let i0 = closest(center, &[]);
+        let mut i1 = closest(points[i0], &[i0]);
+        let mut i2 = NONE;
+        let mut min_radius = f64::INFINITY;
+        for &i in ids {
+            if i == i0 || i == i1 {
+                continue;
+            }
+            let r = circumradius(points[i0], points[i1], points[i]);
+            if r < min_radius {
+                i2 = i;
+                min_radius = r;
+            }
+        }
+        if i2 == NONE {
+            return Vec::new();
+        }
+        if cross(points[i0], points[i1], points[i2]) > 0. {
+            std::mem::swap(&mut i1, &mut i2);
+        }
+        let center = circumcenter(points[i0], points[i1], points[i2]);
+        let mut order = ids.to_vec();
+        order.sort_by(|&a, &b| points[a].distance(center).total_cmp(&points[b].distance(center)));
+
+        let n = points.len();
+        let hash_size = (ids.len() as f64).sqrt().ceil() as usize;
+        let mut s = Sweep {
+            points,
+            triangles: Vec::with_capacity(ids.len() * 6),
+            halfedges: Vec::with_capacity(ids.len() * 6),
+            center,
+            hull_start: i0,
+            hull_prev: vec![NONE; n],
+            hull_next: vec![NONE; n],
+            hull_tri: vec![NONE; n],
+            hull_hash: vec![NONE; hash_size],
+            edge_stack: Vec::new(),
+        };
+        s.hull_next[i0] = i1;
+        s.hull_prev[i2] = i1;
+        s.hull_next[i1] = i2;
+        s.hull_prev[i0] = i2;
+        s.hull_next[i2] = i0;
+        s.hull_prev[i1] = i0;
+        s.hull_tri[i0] = 0;
+        s.hull_tri[i1] = 1;
+        s.hull_tri[i2] = 2;
+        for i in [i0, i1, i2] {
+            let key = s.hash_key(points[i]);
+            s.hull_hash[key] = i;
+        }
+        s.add_triangle(i0, i1, i2, NONE, NONE, NONE);
+
+        for i in order {
+            if i == i0 || i == i1 || i == i2 {
+                continue;
+            }
+            let p = points[i];
+            // Find a hull edge visible from the point, starting from the
+            // hull point at the nearest angle.
+            let key = s.hash_key(p);
+            let mut start = 0;
+            for j in 0..hash_size {
+                start = s.hull_hash[(key + j) % hash_size];
+                if start != NONE && start != s.hull_next[start] {
+                    break;
+                }
+            }
+            start = s.hull_prev[start];
+            let mut e = start;
+            loop {
+                let q = s.hull_next[e];
+                if cross(p, points[e], points[q]) > 0. {
+                    break;
+                }
+                e = q;
+                if e == start {
+                    e = NONE;
+                    break;
+                }
+            }
+            if e == NONE {
+                // Probably a near-duplicate point.
+                continue;
+            }
+
+            // Add the first triangle, then walk forwards and backwards along
+            // the hull adding triangles for the other visible edges.
+            let t = s.add_triangle(e, i, s.hull_next[e], NONE, NONE, s.hull_tri[e]);
+            s.hull_tri[i] = s.legalize(t + 2);
+            s.hull_tri[e] = t;
+            let mut next = s.hull_next[e];
+            loop {
+                let q = s.hull_next[next];
+                if cross(p, points[next], points[q]) <= 0. {
+                    break;
+                }
+                let t = s.add_triangle(next, i, q, s.hull_tri[i], NONE, s.hull_tri[next]);
+                s.hull_tri[i] = s.legalize(t + 2);
+                s.hull_next[next] = next;
+                next = q;
+            }
+            if e == start {
+                loop {
+                    let q = s.hull_prev[e];
+                    if cross(p, points[q], points[e]) <= 0. {
+                        break;
+                    }
+                    let t = s.add_triangle(q, i, e, NONE, s.hull_tri[e], s.hull_tri[q]);
+                    s.legalize(t + 2);
+                    s.hull_tri[q] = t;
+                    s.hull_next[e] = e;
+                    e = q;
+                }
+            }
+            s.hull_start = e;
+            s.hull_prev[i] = e;
+            s.hull_next[e] = i;
+            s.hull_prev[next] = i;
+            s.hull_next[i] = next;
+            let key = s.hash_key(p);
+            s.hull_hash[key] = i;
+            let key = s.hash_key(points[e]);
+            s.hull_hash[key] = e;
+        }
+        s.triangles.chunks_exact(3).map(|t| [t[0], t[2], t[1]]).collect()
+    }
+
+    /// The hash bucket of a point, by its angle around the center.
+    fn hash_key(&self, p: Point) -> usize {
+        let (dx, dy) = (p.x - self.center.x, p.y - self.center.y);
+        // A monotonic stand-in for the angle, from 0 to 1.
+        let r = dx / (dx.abs() + dy.abs());
+        let angle = (if dy > 0. { 3. - r } else { 1. + r }) / 4.;
+        let len = self.hull_hash.len();
+        (angle * len as f64).floor() as usize % len
+    }
+
+    fn add_triangle(&mut self, i0: usize, i1: usize, i2: usize, a: usize, b: usize, c: usize) -> usize {
+        let t = self.triangles.len();
+        self.triangles.extend([i0, i1, i2]);
+        self.halfedges.extend([NONE; 3]);
+        self.link(t, a);
+        self.link(t + 1, b);
+        self.link(t + 2, c);
+        t
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        self.halfedges[a] = b;
+        if b != NONE {
+            self.halfedges[b] = a;
+        }
+    }
+
+    /// Flip edges, starting with `a`, until every triangle around them has an
+    /// empty circumcircle. Returns the half-edge which ends up where the one
+    /// before `a` in its triangle was.
+    fn legalize(&mut self, a: usize) -> usize {
+        let mut a = a;
+        loop {
+            let b = self.halfedges[a];
+            let a0 = a - a % 3;
+            let ar = a0 + (a + 2) % 3;
+            if b == NONE {
+                match self.edge_stack.pop() {
+                    Some(next) => {
+                        a = next;
+                        continue;
+                    }
+                    None => return ar,
+                }
+            }
+            let b0 = b - b % 3;
+            let al = a0 + (a + 1) % 3;
+            let bl = b0 + (b + 2) % 3;
+            let [p0, pr, pl, p1] = [ar, a, al, bl].map(|e| self.points[self.triangles[e]]);
+            if in_circle(p0, pr, pl, p1) {
+                self.triangles[a] = self.triangles[bl];
+                self.triangles[b] = self.triangles[ar];
+                let hbl = self.halfedges[bl];
+                if hbl == NONE {
+                    // The flipped edge was on the hull, so fix the hull's
+                    // reference to it.
+                    let mut e = self.hull_start;
+                    loop {
+                        if self.hull_tri[e] == bl {
+                            self.hull_tri[e] = a;
+                            break;
+                        }
+                        e = self.hull_prev[e];
+                        if e == self.hull_start {
+                            break;
+                        }
+                    }
+                }
+                self.link(a, hbl);
+                self.link(b, self.halfedges[ar]);
+                self.link(ar, bl);
+                self.edge_stack.push(b0 + (b + 1) % 3);
+            } else {
+                match self.edge_stack.pop() {
+                    Some(next) => a = next,
+                    None => return ar,
+                }
+            }
+        }
+    }
+}
+
+/// Whether `p` is inside the circumcircle of the clockwise triangle `a b c`.
+fn in_circle(a: Point, b: Point, c: Point, p: Point) -> bool {
+    let (dx, dy) = (a.x - p.x, a.y - p.y);
+    let (ex, ey) = (b.x - p.x, b.y - p.y);
+    let (fx, fy) = (c.x - p.x, c.y - p.y);
+    let ap = dx * dx + dy * dy;
+    let bp = ex * ex + ey * ey;
+    let cp = fx * fx + fy * fy;
+    dx * (ey * cp - bp * fy) - dy * (ex * cp - bp * fx) + ap * (ex * fy - ey * fx) < 0.
+}
+
+/// The offset from `a` to the circumcenter of the triangle, which is not
+/// finite when the points are collinear.
+fn circumcenter_offset(a: Point, b: Point, c: Point) -> (f64, f64) {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (ex, ey) = (c.x - a.x, c.y - a.y);
+    let bl = dx * dx + dy * dy;
+    let cl = ex * ex + ey * ey;
+    let d = 0.5 / (dx * ey - dy * ex);
+    ((ey * bl - dy * cl) * d, (dx * cl - ex * bl) * d)
+}
+
+/// The squared circumradius of the triangle.
+fn circumradius(a: Point, b: Point, c: Point) -> f64 {
+    let (x, y) = circumcenter_offset(a, b, c);
+    x * x + y * y
+}
+
+fn circumcenter(a: Point, b: Point, c: Point) -> Point {
+    let (x, y) = circumcenter_offset(a, b, c);
+    Point::new(a.x + x, a.y + y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Delaunay;
+    use crate::{point::cross, GeomType, Point, Rect};
+
+    fn grid(n: usize) -> Vec<Point> {
+        // A slightly jittered grid, so most circumcircles are unambiguous.
+        (0..n * n)
+            .map(|i| {
+                let (x, y) = ((i % n) as f64, (i / n) as f64);
+                Point::new(x + 0.1 * (y * 7. % 3.), y + 0.1 * (x * 5. % 3.))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn square() {
+        let points = [(0., 0.), (1., 0.), (1., 1.), (0., 1.)].map(|(x, y)| Point::new(x, y));
+        let delaunay = Delaunay::new(&points);
+        assert_eq!(delaunay.triangles().len(), 2);
+        let rings = delaunay.rings();
+        assert_eq!(rings.iter().map(|r| r.area()).sum::<f64>(), 1.);
+        assert!(rings.iter().all(|r| !r.clockwise()));
+        let geom = delaunay.geom();
+        assert_eq!(geom.geom_type(), GeomType::MultiPolygon);
+        assert_eq!(geom.num_polys(), 2);
+    }
+
+    #[test]
+    fn empty_circumcircles() {
+        let points = grid(12);
+        let delaunay = Delaunay::new(&points);
+        // The triangles cover the convex hull.
+        let area: f64 = delaunay.rings().iter().map(|r| r.area()).sum();
+        assert!((area - crate::Ring::convex_hull_of_points(&points).area()).abs() < 1e-9);
+        for &[a, b, c] in delaunay.triangles() {
+            let [a, b, c] = [a, b, c].map(|i| points[i]);
+            assert!(cross(a, b, c) > 0.);
+            let center = super::circumcenter(a, b, c);
+            let radius = center.distance(a);
+            assert!(points.iter().all(|p| p.distance(center) >= radius - 1e-9));
+        }
+    }
+
+    #[test]
+    fn degenerate_points() {
+        let collinear: Vec<Point> = (0..5).map(|i| Point::new(i as f64, i as f64)).collect();
+        assert!(Delaunay::new(&collinear).triangles().is_empty());
+        assert!(Delaunay::new(&collinear[..2]).triangles().is_empty());
+
+        let points = [(0., 0.), (2., 0.), (0., 0.), (f64::NAN, 1.), (1., 2.)].map(|(x, y)| Point::new(x, y));
+        let delaunay = Delaunay::new(&points);
+        assert_eq!(delaunay.triangles().len(), 1);
+        assert_eq!(delaunay.points().len(), 5);
+    }
+
+    #[test]
+    fn voronoi() {
+        let rect = Rect::new(Point::new(0., 0.), Point::new(10., 10.));
+        let points = grid(5).into_iter().map(|p| Point::new(p.x * 2. + 1., p.y * 2. + 1.)).collect::<Vec<_>>();
+        let cells = Delaunay::new(&points).voronoi_cells(rect);
+        assert_eq!(cells.len(), points.len());
+        let mut total = 0.;
+        for (i, cell) in cells.iter().enumerate() {
+            let cell = cell.as_ref().expect("every point is inside the rect");
+            total += cell.exterior().area();
+            assert!(cell.geom().intersects_xy(points[i].x, points[i].y));
+            // Every point is closer to its own point than to any other.
+            for &p in cell.exterior().points() {
+                let own = p.distance(points[i]);
+                assert!(points.iter().all(|q| q.distance(p) >= own - 1e-9));
+            }
+        }
+        assert!((total - 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn degenerate_voronoi() {
+        let rect = Rect::new(Point::new(0., 0.), Point::new(4., 4.));
+        let points = [(1., 2.), (3., 2.), (1., 2.), (f64::INFINITY, 0.), (50., 2.)].map(|(x, y)| Point::new(x, y));
+        let cells = Delaunay::new(&points).voronoi_cells(rect);
+        let areas: Vec<Option<f64>> = cells.iter().map(|c| c.as_ref().map(|c| c.exterior().area())).collect();
+        assert_eq!(areas, [Some(8.), Some(8.), Some(8.), None, None]);
+    }
+}
@@ -0,0 +1,444 @@
+//! Geometry validation.
+//!
+//! tg builds rings, lines and polygons from whatever points it is given. The
+//! `validate` methods report the ways in which a geometry breaks the OGC
+//! simple features rules, which otherwise surface as wrong answers from the
+//! predicates.
+
+use std::fmt;
+
+use crate::{point::cross, segment::SegmentChain, Geom, GeomType, Line, Point, Poly, Ring, Segment};
+
+/// The line or ring of a geometry in which a problem was found.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Component {
+    /// A point of a Point or MultiPoint.
+    Point,
+    Line,
+    Exterior,
+    Hole(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ValidationErrorKind {
+    /// A line with fewer than 2 points, or a ring with fewer than 3 distinct
+    /// vertices.
+    TooFewPoints,
+    /// A coordinate is NaN or infinite.
+    NonFiniteCoordinate,
+    /// The first and last points of a ring differ. tg closes such rings
+    /// implicitly, but most other consumers do not.
+    UnclosedRing,
+    ZeroArea,
+    /// Two segments of a ring cross or touch away from their shared vertex.
+    SelfIntersection,
+    /// A hole is not covered by the exterior of its polygon.
+    HoleOutsideExterior,
+    /// The interiors of two holes overlap. The other hole is given by index.
+    OverlappingHoles { other: usize },
+}
+
+/// A problem found by `validate`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    /// The index of the polygon, line or point within a multi-geometry or
+    /// geometry collection, counted depth first. Zero for single geometries.
+    pub part: usize,
+    pub component: Component,
+    /// The offending segments, indexed within their ring or line. For
+    /// problems between two rings, the first index is in `component` and the
+    /// second in the other ring. For non-finite coordinates, this is the index
+    /// of the point instead.
+    pub segments: Vec<usize>,
+    /// Where the problem is.
+    pub location: Point,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            ValidationErrorKind::TooFewPoints => "too few points",
+            ValidationErrorKind::NonFiniteCoordinate => "non-finite coordinate",
+            ValidationErrorKind::UnclosedRing => "unclosed ring",
+            ValidationErrorKind::ZeroArea => "zero area ring",
+            ValidationErrorKind::SelfIntersection => "self-intersection",
+            ValidationErrorKind::HoleOutsideExterior => "hole outside exterior",
+            ValidationErrorKind::OverlappingHoles { .. } => "overlapping holes",
+        };
+        write!(
+            f,
+            "{what} in {:?} of part {} at ({}, {})",
+            self.component, self.part, self.location.x, self.location.y
+        )?;
+        if let ValidationErrorKind::OverlappingHoles { other } = self.kind {
+            write!(f, " with Hole({other})")?;
+        }
+        if !self.segments.is_empty() {
+            write!(f, " (segments {:?})", self.segments)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validation
+impl Line {
+    /// Check that the line has at least 2 points, all finite.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Errors::default();
+        errors.check_line(self, Component::Line);
+        errors.list
+    }
+}
+
+/// Validation
+impl Ring {
+    /// Check that the ring has at least 3 distinct vertices, all finite, is
+    /// closed, has a non-zero area and does not intersect itself.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Errors::default();
+        errors.check_ring(self, Component::Exterior);
+        errors.list
+    }
+}
+
+/// Validation
+impl Poly {
+    /// Check each ring as [`Ring::validate`] does, and that the holes lie
+    /// inside the exterior without overlapping each other.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Errors::default();
+        errors.check_poly(self);
+        errors.list
+    }
+}
+
+/// Validation
+impl Geom {
+    /// Check every point, line and polygon of the geometry.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        let mut errors = Errors::default();
+        errors.check_geom(self);
+        errors.list
+    }
+}
+
+#[derive(Default)]
+struct Errors {
+    part: usize,
+    list: Vec<ValidationError>,
+}
+
+impl Errors {
+    fn push(&mut self, kind: ValidationErrorKind, component: Component, segments: Vec<usize>, location: Point) {
+        self.list.push(ValidationError {
+            kind,
+            part: self.part,
+            component,
+            segments,
+            location,
+        });
+    }
+
+    /// Report non-finite points, returning whether there were any.
+    fn check_finite(&mut self, points: &[Point], component: Component) -> bool {
+        let mut found = false;
+        for (i, &point) in points.iter().enumerate() {
+            if !(point.x.is_finite() && point.y.is_finite()) {
+                self.push(ValidationErrorKind::NonFiniteCoordinate, component, vec![i], point);
+                found = true;
+            }
+        }
+        found
+    }
+
+    fn check_point(&mut self, point: Point) {
+        self.check_finite(&[point], Component::Point);
+    }
+
+    fn check_line(&mut self, line: &Line, component: Component) {
+        let points = line.points();
+        if points.len() < 2 {
+            let location = points.first().copied().unwrap_or_default();
+            self.push(ValidationErrorKind::TooFewPoints, component, Vec::new(), location);
+        }
+        self.check_finite(points, component);
+    }
+
+    /// Check a single ring, returning whether it is sound enough for the
+    /// checks between rings.
+    fn check_ring(&mut self, ring: &Ring, component: Component) -> bool {
+        let points = ring.points();
+        let location = points.first().copied().unwrap_or_default();
+        if self.check_finite(points, component) {
+            return false;
+        }
+        let mut distinct = ring.vertices().to_vec();
+        distinct.dedup();
+        if distinct.len() < 3 {
+            self.push(ValidationErrorKind::TooFewPoints, component, Vec::new(), location);
+            return false;
+        }
+        if points.first() != points.last() {
+            self.push(ValidationErrorKind::UnclosedRing, component, Vec::new(), location);
+        }
+        let mut sound = true;
+        if ring.area() == 0. {
+            self.push(ValidationErrorKind::ZeroArea, component, Vec::new(), location);
+            sound = false;
+        }
+        for (i, j, point) in self_intersections(ring) {
+            self.push(ValidationErrorKind::SelfIntersection, component, vec![i, j], point);
+            sound = false;
+        }
+        sound
+    }
+
+    fn check_poly(&mut self, poly: &Poly) {
+        let exterior = poly.exterior();
+        let holes = poly.holes();
+        let exterior_ok = self.check_ring(&exterior, Component::Exterior);
+        let holes_ok: Vec<bool> = holes
+            .iter()
+            .enumerate()
+            .map(|(i, hole)| self.check_ring(hole, Component::Hole(i)))
+            .collect();
+
+        if exterior_ok {
+            let shell = exterior.geom();
+            for (i, hole) in holes.iter().enumerate().filter(|&(i, _)| holes_ok[i]) {
+                if !shell.covers(&hole.geom()) {
+                    let (segments, location) = ring_conflict(hole, &exterior);
+                    self.push(ValidationErrorKind::HoleOutsideExterior, Component::Hole(i), segments, location);
+                }
+            }
+        }
+
+        for i in (0..holes.len()).filter(|&i| holes_ok[i]) {
+            for j in (i + 1..holes.len()).filter(|&j| holes_ok[j]) {
+                let (a, b) = (&holes[i], &holes[j]);
+                if !a.rect().intersects_rect(b.rect()) {
+                    continue;
+                }
+                if let Some((segments, location)) = interiors_overlap(a, b) {
+                    self.push(
+                        ValidationErrorKind::OverlappingHoles { other: j },
+                        Component::Hole(i),
+                        segments,
+                        location,
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_geom(&mut self, geom: &Geom) {
+        if geom.is_empty() {
+            return;
+        }
+        match geom.geom_type() {
+            GeomType::Point => self.check_point(geom.point()),
+            GeomType::LineString => self.check_line(&geom.line().expect("LineString has a line"), Component::Line),
+            GeomType::Polygon => self.check_poly(&geom.poly().expect("Polygon has a poly")),
+            GeomType::MultiPoint => {
+                for point in (0..geom.num_points()).filter_map(|i| geom.point_at(i)) {
+                    self.check_point(point);
+                    self.part += 1;
+                }
+            }
+            GeomType::MultiLineString => {
+                for line in (0..geom.num_lines()).filter_map(|i| geom.line_at(i)) {
+                    self.check_line(&line, Component::Line);
+                    self.part += 1;
+                }
+            }
+            GeomType::MultiPolygon => {
+                for poly in (0..geom.num_polys()).filter_map(|i| geom.poly_at(i)) {
+                    self.check_poly(&poly);
+                    self.part += 1;
+                }
+            }
+            GeomType::GeometryCollection => {
+                for child in (0..geom.num_geometries()).filter_map(|i| geom.geometry_at(i)) {
+                    let multi = matches!(
+                        child.geom_type(),
+                        GeomType::MultiPoint
+                            | GeomType::MultiLineString
+                            | GeomType::MultiPolygon
+                            | GeomType::GeometryCollection
+                    );
+                    self.check_geom(&child);
+                    if !multi {
+                        self.part += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pairs of segments of the ring which intersect other than at the vertex
+/// shared by neighboring segments, found with the ring's index.
+fn self_intersections(ring: &Ring) -> Vec<(usize, usize, Point)> {
+    let chain = SegmentChain::new((0..ring.num_segments()).filter_map(|i| ring.segment(i)), true);
+    let mut found = Vec::new();
+    ring.ring_search(ring, &mut |a: Segment, i: usize, b: Segment, j: usize| {
+        if i >= j {
+            // every pair is visited in both orders, and each segment with itself
+            return true;
+        }
+        if let Some(point) = chain.touch(a, i, b, j) {
+            found.push((i, j, point));
+        }
+        true
+    });
+    found
+}
+
+/// Whether the segments cross at a point interior to both.
+fn crosses(a: Segment, b: Segment) -> bool {
+    let d1 = cross(a.a(), a.b(), b.a());
+    let d2 = cross(a.a(), a.b(), b.b());
+    let d3 = cross(b.a(), b.b(), a.a());
+    let d4 = cross(b.a(), b.b(), a.b());
+    d1 * d2 < 0. && d3 * d4 < 0.
+}
+
+/// The first proper crossing between the segments of two rings.
+fn first_crossing(a: &Ring, b: &Ring) -> Option<(Vec<usize>, Point)> {
+    let mut found = None;
+    a.ring_search(b, &mut |sa: Segment, i: usize, sb: Segment, j: usize| {
+        if crosses(sa, sb) {
+            found = sa.intersection(sb).map(|point| (vec![i, j], point));
+        }
+        found.is_none()
+    });
+    found
+}
+
+/// Explain why `hole` is not covered by `exterior`: a crossing between the
+/// two, or else a vertex of the hole outside the exterior.
+fn ring_conflict(hole: &Ring, exterior: &Ring) -> (Vec<usize>, Point) {
+    if let Some(found) = first_crossing(hole, exterior) {
+        return found;
+    }
+    let shell = exterior.geom();
+    let outside = hole
+        .vertices()
+        .iter()
+        .copied()
+        .find(|p| !shell.intersects_xy(p.x, p.y));
+    (Vec::new(), outside.unwrap_or_else(|| hole.interior_point()))
+}
+
+/// Where the interiors of two rings overlap, if they do. Rings which only
+/// touch along their boundaries do not overlap.
+fn interiors_overlap(a: &Ring, b: &Ring) -> Option<(Vec<usize>, Point)> {
+    if let Some(found) = first_crossing(a, b) {
+        return Some(found);
+    }
+    let (pa, pb) = (a.interior_point(), b.interior_point());
+    if b.geom().intersects_xy(pa.x, pa.y) {
+        Some((Vec::new(), pa))
+    } else if a.geom().intersects_xy(pb.x, pb.y) {
+        Some((Vec::new(), pb))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Component, ValidationErrorKind};
+    use crate::test_util::square;
+    use crate::{Geom, Line, Point, Poly, Ring};
+
+    fn ring(points: &[(f64, f64)]) -> Ring {
+        let points: Vec<Point> = points.iter().map(|&(x, y)| Point::new(x, y)).collect();
+        Ring::new(&points)
+    }
+
+    #[test]
+    fn valid_geometries() {
+        assert!(square(0., 0., 10.).validate().is_empty());
+        let poly = Poly::new(&square(0., 0., 10.), &[square(1., 1., 2.), square(3., 3., 2.)]);
+        assert!(poly.validate().is_empty());
+        assert!(poly.geom().validate().is_empty());
+        assert!(Line::new(&[Point::new(0., 0.), Point::new(1., 1.)]).validate().is_empty());
+    }
+
+    #[test]
+    fn bad_rings() {
+        let errors = ring(&[(0., 0.), (1., 1.)]).validate();
+        assert_eq!(errors[0].kind, ValidationErrorKind::TooFewPoints);
+
+        let errors = ring(&[(0., 0.), (1., 0.), (f64::NAN, 1.), (0., 0.)]).validate();
+        assert_eq!(errors[0].kind, ValidationErrorKind::NonFiniteCoordinate);
+        assert_eq!(errors[0].segments, vec![2]);
+
+        let errors = ring(&[(0., 0.), (1., 0.), (1., 1.)]).validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ValidationErrorKind::UnclosedRing);
+
+        let errors = ring(&[(0., 0.), (1., 1.), (2., 2.), (0., 0.)]).validate();
+        assert_eq!(errors[0].kind, ValidationErrorKind::ZeroArea);
+        assert_eq!(errors[1].kind, ValidationErrorKind::SelfIntersection);
+    }
+
+    #[test]
+    fn self_intersection() {
+        // a bowtie crossing at (1, 1)
+        let bowtie = ring(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.), (0., 0.)]);
+        let errors = bowtie.validate();
+        // the two lobes cancel out, so tg also reports no area
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, ValidationErrorKind::ZeroArea);
+        assert_eq!(errors[1].kind, ValidationErrorKind::SelfIntersection);
+        assert_eq!(errors[1].segments, vec![0, 2]);
+        assert_eq!(errors[1].location, Point::new(1., 1.));
+
+        // a spike which folds back on itself
+        let spike = ring(&[(0., 0.), (4., 0.), (4., 4.), (4., 6.), (4., 5.), (0., 4.), (0., 0.)]);
+        let errors = spike.validate();
+        assert!(errors.iter().any(|e| e.kind == ValidationErrorKind::SelfIntersection));
+
+        // repeated points, including one which wraps around the closing point
+        let repeated = ring(&[(0., 0.), (4., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)]);
+        assert_eq!(repeated.validate(), vec![]);
+        let repeated = ring(&[(0., 0.), (0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.), (0., 0.)]);
+        assert_eq!(repeated.validate(), vec![]);
+    }
+
+    #[test]
+    fn bad_holes() {
+        let poly = Poly::new(
+            &square(0., 0., 10.),
+            &[square(8., 8., 4.), square(1., 1., 3.), square(2., 2., 3.)],
+        );
+        let errors = poly.validate();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert_eq!(errors[0].kind, ValidationErrorKind::HoleOutsideExterior);
+        assert_eq!(errors[0].component, Component::Hole(0));
+        assert_eq!(errors[1].kind, ValidationErrorKind::OverlappingHoles { other: 2 });
+        assert_eq!(errors[1].component, Component::Hole(1));
+
+        let nested = Poly::new(&square(0., 0., 10.), &[square(1., 1., 5.), square(2., 2., 1.)]);
+        let errors = nested.validate();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ValidationErrorKind::OverlappingHoles { other: 1 });
+    }
+
+    #[test]
+    fn parts_of_collections() {
+        let geom = Geom::new_multipolygon(&[
+            Poly::from(square(0., 0., 1.)),
+            Poly::from(ring(&[(0., 0.), (3., 3.), (3., 0.), (0., 2.), (0., 0.)])),
+        ]);
+        let errors = geom.validate();
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert_eq!(errors[0].part, 1);
+        assert_eq!(errors[0].component, Component::Exterior);
+    }
+}
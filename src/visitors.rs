@@ -1,4 +1,4 @@
-use crate::{Segment, Rect};
+use crate::{Point, Rect, Segment};
 
 pub trait NearestSegmentVisitor {
     fn segment_distance(&mut self, segment: Segment, more: &mut i32) -> f64;
@@ -37,3 +37,65 @@ where
         self(a_seg, a_idx, b_seg, b_idx)
     }
 }
+
+/// Finds the segment closest to a point.
+pub(crate) struct NearestToPoint {
+    point: Point,
+    pub(crate) nearest: Option<(Segment, usize, f64)>,
+}
+
+impl NearestToPoint {
+    pub(crate) fn new(point: Point) -> NearestToPoint {
+        NearestToPoint {
+            point,
+            nearest: None,
+        }
+    }
+}
+
+impl NearestSegmentVisitor for NearestToPoint {
+    fn segment_distance(&mut self, segment: Segment, _more: &mut i32) -> f64 {
+        segment.distance_to_point(self.point)
+    }
+
+    fn rect_distance(&mut self, rect: Rect, _more: &mut i32) -> f64 {
+        rect.distance_to_point(self.point)
+    }
+
+    fn visit(&mut self, segment: Segment, distance: f64, index: usize) -> bool {
+        self.nearest = Some((segment, index, distance));
+        false
+    }
+}
+
+/// Finds the segment closest to another segment.
+pub(crate) struct NearestToSegment {
+    segment: Segment,
+    /// The distance, and the closest points on the nearest segment and on
+    /// the query segment.
+    pub(crate) nearest: Option<(f64, Point, Point)>,
+}
+
+impl NearestToSegment {
+    pub(crate) fn new(segment: Segment) -> NearestToSegment {
+        NearestToSegment {
+            segment,
+            nearest: None,
+        }
+    }
+}
+
+impl NearestSegmentVisitor for NearestToSegment {
+    fn segment_distance(&mut self, segment: Segment, _more: &mut i32) -> f64 {
+        segment.distance_to_segment(self.segment)
+    }
+
+    fn rect_distance(&mut self, rect: Rect, _more: &mut i32) -> f64 {
+        self.segment.distance_to_rect(rect)
+    }
+
+    fn visit(&mut self, segment: Segment, _distance: f64, _index: usize) -> bool {
+        self.nearest = Some(segment.closest_points(self.segment));
+        false
+    }
+}
@@ -0,0 +1,145 @@
+//! Reversing lines and rings, and fixing the winding order of polygons.
+
+use crate::{IndexType, Line, Poly, Ring};
+
+/// A convention for the winding order of polygon rings.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// Counter-clockwise exteriors and clockwise holes, as required by
+    /// GeoJSON ([RFC 7946, section 3.1.6](https://www.rfc-editor.org/rfc/rfc7946#section-3.1.6)).
+    Rfc7946,
+    /// Clockwise exteriors and counter-clockwise holes, as used by ESRI
+    /// shapefiles.
+    Shapefile,
+}
+
+/// Orientation
+impl Ring {
+    /// The same ring with its points in reverse order.
+    pub fn reversed(&self) -> Ring {
+        self.reversed_indexed(IndexType::Default)
+    }
+
+    /// Like [`Ring::reversed`], but with the given index.
+    pub fn reversed_indexed(&self, index: IndexType) -> Ring {
+        let mut points = self.points().to_vec();
+        points.reverse();
+        Ring::new_indexed(&points, index)
+    }
+
+    /// The ring wound counter-clockwise, reversing it if needed.
+    pub fn to_counter_clockwise(&self) -> Ring {
+        if self.clockwise() {
+            self.reversed()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// The ring wound clockwise, reversing it if needed.
+    pub fn to_clockwise(&self) -> Ring {
+        if self.clockwise() {
+            self.clone()
+        } else {
+            self.reversed()
+        }
+    }
+}
+
+/// Orientation
+impl Line {
+    /// The same line with its points in reverse order.
+    pub fn reversed(&self) -> Line {
+        self.reversed_indexed(IndexType::Default)
+    }
+
+    /// Like [`Line::reversed`], but with the given index.
+    pub fn reversed_indexed(&self, index: IndexType) -> Line {
+        let mut points = self.points().to_vec();
+        points.reverse();
+        Line::new_indexed(&points, index)
+    }
+}
+
+/// Orientation
+impl Poly {
+    /// The polygon with its rings wound according to `winding`.
+    ///
+    /// Returns a clone of the polygon if it is already wound correctly.
+    pub fn normalize_winding(&self, winding: Winding) -> Poly {
+        let orient = |ring: &Ring, clockwise: bool| {
+            if clockwise {
+                ring.to_clockwise()
+            } else {
+                ring.to_counter_clockwise()
+            }
+        };
+        let exterior_clockwise = winding == Winding::Shapefile;
+        let exterior = self.exterior();
+        let holes = self.holes();
+        if exterior.clockwise() == exterior_clockwise
+            && holes.iter().all(|hole| hole.clockwise() != exterior_clockwise)
+        {
+            return self.clone();
+        }
+        let holes: Vec<Ring> = holes.iter().map(|hole| orient(hole, !exterior_clockwise)).collect();
+        Poly::new(&orient(&exterior, exterior_clockwise), &holes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Winding;
+    use crate::test_util::square;
+    use crate::{IndexType, Line, Point, Poly, Ring};
+
+    #[test]
+    fn reverse() {
+        let ring = square(0., 0., 1.);
+        assert!(!ring.clockwise());
+        let reversed = ring.reversed();
+        assert!(reversed.clockwise());
+        assert_eq!(reversed.point(1), Some(Point::new(0., 1.)));
+        assert_eq!(reversed.to_counter_clockwise(), ring);
+        assert_eq!(ring.to_clockwise(), reversed);
+        assert_eq!(ring.to_counter_clockwise(), ring);
+
+        let line = Line::new(&[Point::new(0., 0.), Point::new(1., 0.), Point::new(1., 1.)]);
+        assert_eq!(
+            line.reversed().points(),
+            &[Point::new(1., 1.), Point::new(1., 0.), Point::new(0., 0.)]
+        );
+    }
+
+    #[test]
+    fn reverse_indexed() {
+        let mut points: Vec<Point> = (0..64)
+            .map(|i| {
+                let a = i as f64 / 64. * std::f64::consts::TAU;
+                Point::new(a.cos(), a.sin())
+            })
+            .collect();
+        points.push(points[0]);
+        let ring = Ring::new(&points);
+        assert!(ring.reversed().index_num_levels() > 0);
+        assert_eq!(ring.reversed_indexed(IndexType::None).index_num_levels(), 0);
+        let line = Line::new(&points);
+        assert!(line.reversed().index_num_levels() > 0);
+        assert_eq!(line.reversed_indexed(IndexType::None).index_num_levels(), 0);
+    }
+
+    #[test]
+    fn normalize_polygon() {
+        let exterior = square(0., 0., 10.).reversed();
+        let hole = square(2., 2., 2.);
+        let poly = Poly::new(&exterior, &[hole]);
+
+        let geojson = poly.normalize_winding(Winding::Rfc7946);
+        assert!(!geojson.exterior().clockwise());
+        assert!(geojson.hole(0).unwrap().clockwise());
+        assert_eq!(geojson.normalize_winding(Winding::Rfc7946), geojson);
+
+        let shapefile = geojson.normalize_winding(Winding::Shapefile);
+        assert_eq!(shapefile, poly);
+    }
+}